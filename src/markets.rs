@@ -1,16 +1,59 @@
-use near_sdk::{near_bindgen, env};
+use near_sdk::{near_bindgen, env, Promise, PromiseResult};
 use borsh::{BorshDeserialize, BorshSerialize};
 use std::collections::{BTreeMap, HashMap};
 use serde::{Deserialize, Serialize};
 
 mod market;
+mod oracle;
+mod time;
+mod listing;
+mod archive;
+mod storage;
+mod depth;
+mod portfolio;
+mod claim;
+mod operator;
+mod pause;
+mod migration;
+mod roles;
+mod events;
+mod market_options;
+mod limits;
 type Market = market::Market;
 type Order = market::orderbook::order::Order;
 type ResolutionWindow = market::ResolutionWindow;
+type MarketStatus = market::MarketStatus;
+type MarketMetadata = market::metadata::MarketMetadata;
+type SelfTradePrevention = market::self_trade::SelfTradePrevention;
+type OrderOptions = market::order_type::OrderOptions;
+type MarketOptions = market_options::MarketOptions;
+type OracleReport = oracle::OracleReport;
+type WindowConfig = time::WindowConfig;
+type MarketTimeline = time::MarketTimeline;
+type MarketFilter = listing::MarketFilter;
+type MarketSummary = listing::MarketSummary;
+type ArchivedMarket = archive::ArchivedMarket;
+type PriceLevel = market::orderbook::PriceLevel;
+type DepthPoint = depth::DepthPoint;
+type Quote = depth::Quote;
+type Trade = market::trades::Trade;
+type Candle = market::trades::Candle;
+type PortfolioEntry = portfolio::PortfolioEntry;
+type ClaimResult = claim::ClaimResult;
+type OperatorApproval = operator::OperatorApproval;
+type Action = pause::Action;
+type PauseFlags = pause::PauseFlags;
+type CircuitBreaker = pause::CircuitBreaker;
+type Role = roles::Role;
+type OrderLimits = limits::OrderLimits;
+type StorageAccount = storage::StorageAccount;
+type StorageBalance = storage::StorageBalance;
+type StorageBalanceBounds = storage::StorageBalanceBounds;
 
 #[near_bindgen]
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug)]
 struct Markets {
+	state_version: u32, // Has to stay the first field, see migration
 	creator: String,
 	active_markets: BTreeMap<u64, Market>,
 	nonce: u64,
@@ -21,11 +64,40 @@ struct Markets {
 	user_count: u64,
 	max_fee_percentage: u128,
 	creation_bond: u128,
+	default_window_config: WindowConfig,
+	designated_reporter_bond: u128,
+	markets_by_category: HashMap<String, Vec<u64>>,
+	markets_by_creator: HashMap<String, Vec<u64>>,
+	archived_markets: BTreeMap<u64, ArchivedMarket>,
+	treasury: String,
+	claim_period: u64, // In ms after finalization, unclaimed funds can be swept to the treasury afterwards
+	storage_accounts: HashMap<String, StorageAccount>,
+	markets_by_account: HashMap<String, Vec<u64>>, // Markets an account traded or staked in and hasn't claimed yet
+	operators: HashMap<String, HashMap<String, OperatorApproval>>, // Account to operator to what the operator is allowed to do
+	global_pause: PauseFlags,
+	market_pauses: HashMap<u64, PauseFlags>,
+	circuit_breaker: CircuitBreaker,
+	roles: HashMap<String, Vec<Role>>,
+	judge_threshold: u64, // Number of judges that have to agree on a payout to finalize a market that's awaiting the judge
+	moderation_enabled: bool, // New markets wait for a moderator's approval before they open
+	allowed_categories: Vec<String>,
+	allowed_tags: Vec<String>, // Outcome tags are free-form while this is empty
+	self_trade_prevention: SelfTradePrevention, // Used for orders that don't pick a mode
+	order_limits: OrderLimits, // Used for markets that don't override them
 }
 
 #[near_bindgen]
 impl Markets {
 
+	// Converts the deployed state to the current layout, the owner calls this right after deploying a version that changes the state
+	#[init]
+	pub fn migrate() -> Self {
+		let state = env::storage_read(b"STATE").expect("no state to migrate");
+		let markets = migration::migrate_state(&state);
+		assert!(markets.caller_has_role(Role::Owner), "only the owner can migrate");
+		return markets;
+	}
+
 	fn dai_token(
 		&self
 	) -> u128 {
@@ -39,7 +111,7 @@ impl Markets {
 		amount: u128
 	) {
 		let account_id = env::predecessor_account_id();
-		assert_eq!(account_id, self.creator);
+		assert!(self.caller_has_role(Role::Minter), "only minters can mint fdai");
 
 		*self.fdai_balances.get_mut(&account_id).unwrap() += amount;
 
@@ -56,6 +128,7 @@ impl Markets {
 		let can_claim = self.fdai_balances.get(&account_id).is_none();
 		assert!(can_claim, "user has already claimed fdai");
 
+		self.use_storage(&account_id, None, storage::BALANCE_ENTRY_BYTES);
 		let claim_amount = 100 * self.dai_token();
 		self.fdai_balances.insert(account_id, claim_amount);
 
@@ -65,6 +138,33 @@ impl Markets {
 		self.user_count = self.user_count + 1;
 	}
 
+	pub fn set_creation_bond(
+		&mut self,
+		creation_bond: u128
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can update the creation bond");
+		self.creation_bond = creation_bond;
+	}
+
+	// Applies to markets created after the update
+	pub fn set_designated_reporter_bond(
+		&mut self,
+		bond: u128
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can update the designated reporter bond");
+		self.designated_reporter_bond = bond;
+	}
+
+	// Used for markets that don't specify their own windows, applies to markets created after the update
+	pub fn set_default_window_config(
+		&mut self,
+		window_config: WindowConfig
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can update the default window config");
+		assert!(window_config.dispute_window > 0, "dispute window can't be empty");
+		self.default_window_config = window_config;
+	}
+
 	pub fn get_fdai_balance(&self, account_id: String) -> u128 {
 		return *self.fdai_balances.get(&account_id).unwrap_or(&0);
 	}
@@ -80,34 +180,564 @@ impl Markets {
 		fee_percentage: u128,
 		cost_percentage: u128,
 		api_source: String
+	) -> u64 {
+		return self.create_market_with_options(description, extra_info, outcomes, outcome_tags, categories, end_time, fee_percentage, cost_percentage, api_source, MarketOptions::default());
+	}
+
+	pub fn create_market_with_options(
+		&mut self,
+		description: String,
+		extra_info: String,
+		outcomes: u64,
+		outcome_tags: Vec<String>,
+		categories: Vec<String>,
+		end_time: u64,
+		fee_percentage: u128,
+		cost_percentage: u128,
+		api_source: String,
+		options: MarketOptions
 	) -> u64 {
 		assert!(outcomes > 1);
 		assert!(outcomes == 2 || outcomes == outcome_tags.len() as u64);
 		assert!(outcomes < 20); // up for change
-		assert!(end_time > env::block_timestamp() / 1000000);
+		assert!(end_time > time::now_ms());
 		assert!(categories.len() < 6);
 		assert!(fee_percentage <= self.max_fee_percentage);
 		assert!(fee_percentage >= cost_percentage);
+		assert!(description.len() <= market::metadata::MAX_TEXT_LENGTH, "description is too long");
+		assert!(extra_info.len() <= market::metadata::MAX_TEXT_LENGTH, "extra info is too long");
+		assert!(api_source.len() <= market::metadata::MAX_URL_LENGTH, "api source is too long");
 
 		if outcomes == 2 {assert!(outcome_tags.len() == 0)}
 		// TODO check if end_time hasn't happened yet
 		let account_id = env::predecessor_account_id();
+		let designated_reporter = options.designated_reporter.unwrap_or(account_id.to_string());
+		let window_config = options.window_config.unwrap_or(self.default_window_config.clone());
+		assert!(window_config.dispute_window > 0, "dispute window can't be empty");
+		for (i, category) in categories.iter().enumerate() {
+			assert!(self.allowed_categories.contains(category), "category isn't allowed");
+			assert!(!categories[..i].contains(category), "duplicate category");
+		}
+		for (i, tag) in outcome_tags.iter().enumerate() {
+			assert!(self.allowed_tags.is_empty() || self.allowed_tags.contains(tag), "tag isn't allowed");
+			assert!(!outcome_tags[..i].contains(tag), "duplicate tag");
+		}
+		let metadata = options.metadata.unwrap_or(MarketMetadata::from_legacy(&description, &extra_info, &outcome_tags));
+		metadata.assert_valid(outcomes);
+		metadata.assert_labels_match(&outcome_tags);
+
+		// Creation bond is returned once the designated reporter reports in time
+		if self.creation_bond > 0 {
+			assert!(self.get_fdai_balance(account_id.to_string()) >= self.creation_bond, "not enough balance to cover creation bond");
+			self.subtract_balance(self.creation_bond, account_id.to_string());
+		}
 
-		// TODO: Escrow bond account_id creator's account
-		let new_market = Market::new(self.nonce, account_id, description, extra_info, outcomes, outcome_tags, categories, end_time, fee_percentage, cost_percentage, api_source);
+		let mut new_market = Market::new(self.nonce, account_id, description, extra_info, outcomes, outcome_tags, categories, end_time, fee_percentage, cost_percentage, api_source, options.oracle, designated_reporter, window_config, self.designated_reporter_bond, self.creation_bond, metadata);
+		if self.moderation_enabled {
+			new_market.status = MarketStatus::Pending;
+		}
 		let market_id = new_market.id;
+		for category in &new_market.categories {
+			self.markets_by_category.entry(category.to_string()).or_insert(Vec::new()).push(market_id);
+		}
+		self.markets_by_creator.entry(new_market.creator.to_string()).or_insert(Vec::new()).push(market_id);
 		self.active_markets.insert(self.nonce, new_market);
 		self.nonce = self.nonce + 1;
 		return market_id;
 	}
 
+	// Resolution relevant fields are locked, the rest can be changed by the creator until the market is first traded
+	pub fn update_market_metadata(
+		&mut self,
+		market_id: u64,
+		title: Option<String>,
+		image_hash: Option<String>,
+		language: Option<String>
+	) {
+		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+		assert_eq!(market.creator, env::predecessor_account_id(), "only the creator can edit the market");
+		assert_eq!(market.trade_history.total, 0, "market has already been traded");
+		market.metadata.update(title, image_hash, language);
+	}
+
 	pub fn delete_market(
 		&mut self,
 		market_id: u64
+	) {
+		assert!(self.caller_has_role(Role::MarketModerator), "only market moderators can delete markets");
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		assert_eq!(market.get_held_total(), 0, "market still holds funds, void it first");
+
+		let market = self.active_markets.remove(&market_id).unwrap();
+		self.remove_from_indexes(&market);
+		for (account_id, bytes_used) in &market.storage_by_account {
+			self.release_storage(account_id, None, *bytes_used);
+		}
+	}
+
+	pub fn set_moderation(
+		&mut self,
+		enabled: bool
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can update moderation");
+		self.moderation_enabled = enabled;
+	}
+
+	// Curated list new markets have to pick their categories from, existing markets keep theirs
+	pub fn set_allowed_categories(
+		&mut self,
+		categories: Vec<String>
+	) {
+		assert!(self.caller_has_role(Role::MarketModerator), "only market moderators can update the allowed categories");
+		self.allowed_categories = categories;
+	}
+
+	pub fn get_allowed_categories(
+		&self
+	) -> &Vec<String> {
+		return &self.allowed_categories;
+	}
+
+	// Curated list new markets have to pick their outcome tags from, an empty list lets creators name outcomes freely
+	pub fn set_allowed_tags(
+		&mut self,
+		tags: Vec<String>
+	) {
+		assert!(self.caller_has_role(Role::MarketModerator), "only market moderators can update the allowed tags");
+		self.allowed_tags = tags;
+	}
+
+	pub fn get_allowed_tags(
+		&self
+	) -> &Vec<String> {
+		return &self.allowed_tags;
+	}
+
+	pub fn approve_market(
+		&mut self,
+		market_id: u64
+	) {
+		assert!(self.caller_has_role(Role::MarketModerator), "only market moderators can approve markets");
+		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+		market.approve();
+		events::log_event("market_approved", serde_json::json!({ "market_id": market_id, "by": env::predecessor_account_id() }));
+	}
+
+	// Voids the pending market, which returns the creation bond to the creator
+	pub fn reject_market(
+		&mut self,
+		market_id: u64
+	) {
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		assert_eq!(market.get_status(), MarketStatus::Pending, "market isn't pending approval");
+		self.void_market(market_id);
+		events::log_event("market_rejected", serde_json::json!({ "market_id": market_id, "by": env::predecessor_account_id() }));
+	}
+
+	// Calls the market off, participants claim back their spend and stakes through claim_earnings
+	pub fn void_market(
+		&mut self,
+		market_id: u64
+	) {
+		assert!(self.caller_has_role(Role::MarketModerator), "only market moderators can void markets");
+		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+		market.void();
+
+		let creation_bond = market.creation_bond;
+		market.creation_bond = 0;
+		let creator = market.creator.to_string();
+		let pending_request = market.pending_oracle_request.take();
+		let resolute_bond = market.resolute_bond;
+
+		if creation_bond > 0 {
+			self.add_balance(creation_bond, creator);
+		}
+		// The oracle callback fails once the request is gone so the escrowed bond is returned here
+		if let Some(requester) = pending_request {
+			self.add_balance(resolute_bond, requester);
+		}
+		events::log_event("market_voided", serde_json::json!({ "market_id": market_id, "by": env::predecessor_account_id() }));
+	}
+
+	// Replaces any earlier approval of the operator, which resets what it has spent
+	pub fn approve_operator(
+		&mut self,
+		operator_id: String,
+		market_id: Option<u64>,
+		spend_limit: Option<u128>
+	) {
+		let account_id = env::predecessor_account_id();
+		assert_ne!(account_id, operator_id, "can't approve yourself as operator");
+		self.operators.entry(account_id).or_insert(HashMap::new()).insert(operator_id, OperatorApproval::new(market_id, spend_limit));
+	}
+
+	pub fn revoke_operator(
+		&mut self,
+		operator_id: String
 	) {
 		let account_id = env::predecessor_account_id();
-		assert_eq!(account_id, self.creator, "markets can only be deleted by the market creator");
-		self.active_markets.remove(&market_id);
+		let approvals = self.operators.get_mut(&account_id).expect("operator isn't approved");
+		assert!(approvals.remove(&operator_id).is_some(), "operator isn't approved");
+	}
+
+	pub fn get_operator_approval(
+		&self,
+		account_id: String,
+		operator_id: String
+	) -> Option<&OperatorApproval> {
+		return self.operators.get(&account_id).and_then(|approvals| approvals.get(&operator_id));
+	}
+
+	// The account itself is always authorized, operators are checked against and charged on their approval
+	fn authorize(
+		&mut self,
+		account_id: &String,
+		market_id: u64,
+		spend: u128
+	) -> Result<(), String> {
+		let operator_id = env::predecessor_account_id();
+		if &operator_id == account_id { return Ok(()) }
+
+		let approval = self.operators.get_mut(account_id).and_then(|approvals| approvals.get_mut(&operator_id));
+		return match approval {
+			Some(approval) => approval.use_approval(market_id, spend),
+			None => Err("not authorized to act for this account".to_string())
+		};
+	}
+
+	// Credits refunds of orders an operator acted on back to its approval, nothing to do if the account acts itself
+	fn credit_operator(
+		&mut self,
+		account_id: &String,
+		amount: u128
+	) {
+		let operator_id = env::predecessor_account_id();
+		if &operator_id == account_id || amount == 0 { return }
+
+		if let Some(approval) = self.operators.get_mut(account_id).and_then(|approvals| approvals.get_mut(&operator_id)) {
+			approval.credit(amount);
+		}
+	}
+
+	fn assert_authorized(
+		&mut self,
+		account_id: &String,
+		market_id: u64,
+		spend: u128
+	) {
+		if let Err(error) = self.authorize(account_id, market_id, spend) {
+			panic!("{}", error);
+		}
+	}
+
+	// Pauses action, or everything if no action is given, on market_id or on all markets if no market is given
+	pub fn pause(
+		&mut self,
+		market_id: Option<u64>,
+		action: Option<Action>
+	) {
+		self.set_paused(market_id, action, true);
+	}
+
+	pub fn unpause(
+		&mut self,
+		market_id: Option<u64>,
+		action: Option<Action>
+	) {
+		self.set_paused(market_id, action, false);
+	}
+
+	fn set_paused(
+		&mut self,
+		market_id: Option<u64>,
+		action: Option<Action>,
+		paused: bool
+	) {
+		assert!(self.caller_has_role(Role::Pauser), "only pausers can pause");
+		match market_id {
+			Some(market_id) => {
+				let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+				let flags = self.market_pauses.entry(market_id).or_insert(PauseFlags::default());
+				flags.set(action, paused);
+				// Trades before the unpause already tripped the circuit breaker, they shouldn't trip it again
+				if !paused && !flags.is_paused(Action::Trading) {
+					market.circuit_breaker_from = market.trade_history.total;
+				}
+			},
+			None => self.global_pause.set(action, paused)
+		}
+	}
+
+	pub fn get_pause_flags(
+		&self,
+		market_id: Option<u64>
+	) -> PauseFlags {
+		return match market_id {
+			Some(market_id) => self.market_pauses.get(&market_id).cloned().unwrap_or(PauseFlags::default()),
+			None => self.global_pause.clone()
+		};
+	}
+
+	pub fn set_circuit_breaker(
+		&mut self,
+		max_price_move: u128,
+		window: u64
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can update the circuit breaker");
+		self.circuit_breaker = CircuitBreaker { max_price_move, window };
+	}
+
+	fn is_paused(
+		&self,
+		market_id: u64,
+		action: Action
+	) -> bool {
+		let market_paused = self.market_pauses.get(&market_id).map(|flags| flags.is_paused(action)).unwrap_or(false);
+		return self.global_pause.is_paused(action) || market_paused;
+	}
+
+	fn assert_not_paused(
+		&self,
+		market_id: u64,
+		action: Action
+	) {
+		assert!(!self.is_paused(market_id, action), "{} is paused", action.name());
+	}
+
+	// Trips after the fill that moved the price too much, that order goes through and trading stays paused until unpaused
+	fn check_circuit_breaker(
+		&mut self,
+		market_id: u64
+	) {
+		if self.circuit_breaker.max_price_move == 0 { return }
+		let market = self.active_markets.get(&market_id).unwrap();
+		let window_start = time::now_ms().saturating_sub(self.circuit_breaker.window);
+		if market.get_max_price_move_since(window_start) > self.circuit_breaker.max_price_move {
+			self.market_pauses.entry(market_id).or_insert(PauseFlags::default()).set(Some(Action::Trading), true);
+		}
+	}
+
+	pub fn grant_role(
+		&mut self,
+		account_id: String,
+		role: Role
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can grant roles");
+		let roles = self.roles.entry(account_id.to_string()).or_insert(Vec::new());
+		if roles.contains(&role) { return }
+		roles.push(role);
+		events::log_event("role_granted", serde_json::json!({ "account_id": account_id, "role": role, "by": env::predecessor_account_id() }));
+	}
+
+	pub fn revoke_role(
+		&mut self,
+		account_id: String,
+		role: Role
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can revoke roles");
+		assert!(self.has_role(account_id.to_string(), role), "account doesn't have this role");
+		assert!(role != Role::Owner || self.get_role_members(Role::Owner).len() > 1, "can't revoke the last owner");
+		assert!(role != Role::Judge || self.get_role_members(Role::Judge).len() as u64 > self.judge_threshold, "not enough judges would be left to reach the judge threshold");
+		let roles = self.roles.get_mut(&account_id).unwrap();
+		roles.retain(|granted_role| granted_role != &role);
+		if roles.is_empty() {
+			self.roles.remove(&account_id);
+		}
+		// Votes of judges that were revoked don't count anymore
+		if role == Role::Judge {
+			for market in self.active_markets.values_mut() {
+				market.judge_votes.remove(&account_id);
+			}
+		}
+		events::log_event("role_revoked", serde_json::json!({ "account_id": account_id, "role": role, "by": env::predecessor_account_id() }));
+	}
+
+	pub fn has_role(
+		&self,
+		account_id: String,
+		role: Role
+	) -> bool {
+		return self.roles.get(&account_id).map(|roles| roles.contains(&role)).unwrap_or(false);
+	}
+
+	pub fn get_roles(
+		&self,
+		account_id: String
+	) -> Vec<Role> {
+		return self.roles.get(&account_id).cloned().unwrap_or(vec![]);
+	}
+
+	// Sorted by account id
+	pub fn get_role_members(
+		&self,
+		role: Role
+	) -> Vec<String> {
+		let mut members: Vec<String> = self.roles.iter()
+		.filter(|(_, roles)| roles.contains(&role))
+		.map(|(account_id, _)| account_id.to_string())
+		.collect();
+		members.sort();
+		return members;
+	}
+
+	pub fn set_judge_threshold(
+		&mut self,
+		judge_threshold: u64
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can update the judge threshold");
+		assert!(judge_threshold > 0, "judge threshold should be larger than 0");
+		assert!(judge_threshold <= self.get_role_members(Role::Judge).len() as u64, "judge threshold can't be larger than the number of judges");
+		self.judge_threshold = judge_threshold;
+		events::log_event("judge_threshold_updated", serde_json::json!({ "judge_threshold": judge_threshold }));
+	}
+
+	pub fn get_judge_threshold(
+		&self
+	) -> u64 {
+		return self.judge_threshold;
+	}
+
+	pub fn set_self_trade_prevention(
+		&mut self,
+		self_trade_prevention: SelfTradePrevention
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can update the self trade prevention default");
+		self.self_trade_prevention = self_trade_prevention;
+	}
+
+	pub fn get_self_trade_prevention(
+		&self
+	) -> SelfTradePrevention {
+		return self.self_trade_prevention;
+	}
+
+	pub fn set_order_limits(
+		&mut self,
+		order_limits: OrderLimits
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can update the order limits");
+		self.order_limits = order_limits;
+	}
+
+	// Overrides the protocol's order limits for market_id, None goes back to the protocol's
+	pub fn set_market_order_limits(
+		&mut self,
+		market_id: u64,
+		order_limits: Option<OrderLimits>
+	) {
+		assert!(self.caller_has_role(Role::MarketModerator), "only market moderators can update market order limits");
+		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+		market.order_limits = order_limits;
+	}
+
+	// Limits that apply to market_id, the protocol's if no market is given
+	pub fn get_order_limits(
+		&self,
+		market_id: Option<u64>
+	) -> OrderLimits {
+		return market_id
+		.and_then(|market_id| self.active_markets.get(&market_id).expect("market doesn't exist").order_limits.clone())
+		.unwrap_or(self.order_limits.clone());
+	}
+
+	pub fn get_judge_votes(
+		&self,
+		market_id: u64
+	) -> HashMap<String, Option<Vec<u128>>> {
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		return market.judge_votes.clone();
+	}
+
+	fn caller_has_role(
+		&self,
+		role: Role
+	) -> bool {
+		return self.has_role(env::predecessor_account_id(), role);
+	}
+
+	fn track_market_for(
+		&mut self,
+		account_id: &String,
+		market_id: u64
+	) {
+		let market_ids = self.markets_by_account.entry(account_id.to_string()).or_insert(Vec::new());
+		if !market_ids.contains(&market_id) {
+			market_ids.push(market_id);
+		}
+	}
+
+	fn remove_from_indexes(
+		&mut self,
+		market: &Market
+	) {
+		for category in &market.categories {
+			self.markets_by_category.get_mut(category).unwrap().retain(|id| id != &market.id);
+		}
+		self.markets_by_creator.get_mut(&market.creator).unwrap().retain(|id| id != &market.id);
+		// Every account that traded or staked in the market was charged storage in it
+		for account_id in market.storage_by_account.keys() {
+			if let Some(market_ids) = self.markets_by_account.get_mut(account_id) {
+				market_ids.retain(|id| id != &market.id);
+				if market_ids.is_empty() {
+					self.markets_by_account.remove(account_id);
+				}
+			}
+		}
+		self.market_pauses.remove(&market.id);
+	}
+
+	// Permissionless, compacts a finalized market once everything is claimed or the claim period has passed
+	pub fn archive_market(
+		&mut self,
+		market_id: u64
+	) {
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		assert!(market.is_finalized(), "market isn't finalized yet");
+		let unclaimed = market.get_unclaimed_total();
+		let claim_deadline = market.finalization_time.expect("market isn't finalized yet") + self.claim_period;
+		assert!(unclaimed == 0 || time::now_ms() >= claim_deadline, "market still has unclaimed funds");
+
+		let market = self.active_markets.remove(&market_id).unwrap();
+		self.remove_from_indexes(&market);
+		for (account_id, bytes_used) in &market.storage_by_account {
+			self.release_storage(account_id, None, *bytes_used);
+		}
+		self.archived_markets.insert(market_id, ArchivedMarket::from_market(&market, time::now_ms(), unclaimed));
+
+		if unclaimed > 0 {
+			self.add_balance(unclaimed, self.treasury.to_string());
+		}
+	}
+
+	pub fn get_archived_market(
+		&self,
+		market_id: u64
+	) -> &ArchivedMarket {
+		return self.archived_markets.get(&market_id).expect("market isn't archived");
+	}
+
+	pub fn get_archived_markets(
+		&self,
+		from_index: u64,
+		limit: u64
+	) -> Vec<&ArchivedMarket> {
+		return self.archived_markets.range(from_index..).take(limit as usize).map(|(_, market)| market).collect();
+	}
+
+	pub fn set_treasury(
+		&mut self,
+		treasury: String
+	) {
+		assert!(self.caller_has_role(Role::FeeCollector), "only fee collectors can update the treasury");
+		self.treasury = treasury;
+	}
+
+	pub fn set_claim_period(
+		&mut self,
+		claim_period: u64
+	) {
+		assert!(self.caller_has_role(Role::Owner), "only the owner can update the claim period");
+		self.claim_period = claim_period;
 	}
 
 	pub fn place_order(
@@ -116,17 +746,71 @@ impl Markets {
 		outcome: u64, 
 		spend: u128, 
 		price: u128
-	) {
-		let account_id = env::predecessor_account_id();
+	) -> u128 {
+		return self.place_order_for(env::predecessor_account_id(), market_id, outcome, spend, price);
+	}
+
+	// Places an order for account_id, only callable by the account itself or its operators
+	pub fn place_order_for(
+		&mut self,
+		account_id: String,
+		market_id: u64, 
+		outcome: u64, 
+		spend: u128, 
+		price: u128
+	) -> u128 {
+		return self.place_order_with_options_for(account_id, market_id, outcome, spend, price, OrderOptions::default());
+	}
+
+	pub fn place_order_with_options(
+		&mut self,
+		market_id: u64,
+		outcome: u64,
+		spend: u128,
+		price: u128,
+		options: OrderOptions
+	) -> u128 {
+		return self.place_order_with_options_for(env::predecessor_account_id(), market_id, outcome, spend, price, options);
+	}
+
+	// Returns the spend that was left unfilled and refunded instead of resting in the book. That's what immediate or cancel and self trade prevention cancelled
+	// and what's left once matching stops at market::MAX_MATCH_ITERATIONS while the order still crosses the book
+	pub fn place_order_with_options_for(
+		&mut self,
+		account_id: String,
+		market_id: u64,
+		outcome: u64,
+		spend: u128,
+		price: u128,
+		options: OrderOptions
+	) -> u128 {
 		let balance = self.get_fdai_balance(account_id.to_string());
 		assert!(balance >= spend, "insufficient balance");
 
 		let amount_of_shares = spend / price;
 		let rounded_spend = amount_of_shares * price;
-		let market = self.active_markets.get_mut(&market_id).unwrap();
-		market.create_order(account_id.to_string(), outcome, amount_of_shares, rounded_spend, price);
-
-		self.subtract_balance(rounded_spend);
+		self.assert_authorized(&account_id, market_id, rounded_spend);
+		self.assert_not_paused(market_id, Action::Trading);
+		let limits = self.get_order_limits(Some(market_id));
+		assert!(rounded_spend >= limits.min_order_size, "order is below the minimum size");
+		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+		let self_trade_prevention = options.self_trade_prevention.unwrap_or(self.self_trade_prevention);
+		let (unfilled, refunded, removed_orders, placed) = market.create_order(account_id.to_string(), outcome, amount_of_shares, rounded_spend, price, self_trade_prevention, options.order_type.unwrap_or_default(), &limits);
+		self.check_circuit_breaker(market_id);
+
+		self.subtract_balance(rounded_spend, account_id.to_string());
+		if unfilled + refunded > 0 {
+			self.add_balance(unfilled + refunded, account_id.to_string());
+			self.credit_operator(&account_id, unfilled + refunded);
+		}
+		if removed_orders > 0 {
+			self.release_storage(&account_id, Some(market_id), removed_orders * storage::ORDER_ENTRY_BYTES);
+		}
+		if placed {
+			self.use_storage(&account_id, Some(market_id), storage::ORDER_ENTRY_BYTES);
+			self.track_market_for(&account_id, market_id);
+		}
+		return unfilled;
 	}
 
 	// TODO: Subtract liquidity
@@ -136,81 +820,337 @@ impl Markets {
 		outcome: u64, 
 		order_id: u128
 	) {
-		let account_id = env::predecessor_account_id();
-		let market = self.active_markets.get_mut(&market_id).unwrap();
-		assert_eq!(market.resoluted, false);
-		let mut orderbook = market.orderbooks.get_mut(&outcome).unwrap();
-		let order = orderbook.open_orders.get(&order_id).unwrap();
+		self.cancel_order_for(env::predecessor_account_id(), market_id, outcome, order_id);
+	}
+
+	// Cancels an order of account_id, only callable by the account itself or its operators. Orders can't fill after end_time so what's left of them can be withdrawn at any time,
+	// claiming takes the remaining open orders off the book
+	pub fn cancel_order_for(
+		&mut self,
+		account_id: String,
+		market_id: u64, 
+		outcome: u64, 
+		order_id: u128
+	) {
+		self.assert_authorized(&account_id, market_id, 0);
+		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+		let mut orderbook = market.orderbooks.get_mut(&outcome).expect("outcome doesn't exist");
+		let order = orderbook.open_orders.get(&order_id).expect("order isn't open");
 		assert!(account_id == order.creator);
+		// Partially filled orders stay around as filled orders so their storage isn't freed yet
+		let frees_storage = order.shares_filled == 0;
 		let to_return = orderbook.remove_order(order_id);
-		self.add_balance(to_return, account_id);
+		self.add_balance(to_return, account_id.to_string());
+		self.credit_operator(&account_id, to_return);
+		if frees_storage {
+			self.release_storage(&account_id, Some(market_id), storage::ORDER_ENTRY_BYTES);
+		}
     }
 
+	// Kill switch for market makers, cancels the caller's open orders in market_id or in every market it's in, optionally only for outcome
+	pub fn cancel_all_orders(
+		&mut self,
+		market_id: Option<u64>,
+		outcome: Option<u64>
+	) -> u128 {
+		return self.cancel_all_orders_for(env::predecessor_account_id(), market_id, outcome);
+	}
+
+	// Returns the total refunded, without a market_id markets the caller isn't authorized for are skipped
+	pub fn cancel_all_orders_for(
+		&mut self,
+		account_id: String,
+		market_id: Option<u64>,
+		outcome: Option<u64>
+	) -> u128 {
+		let market_ids = match market_id {
+			Some(market_id) => {
+				self.assert_authorized(&account_id, market_id, 0);
+				let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+				if let Some(outcome) = outcome {
+					assert!(outcome < market.outcomes, "outcome doesn't exist");
+				}
+				vec![market_id]
+			},
+			None => self.markets_by_account.get(&account_id).cloned().unwrap_or(vec![])
+		};
+
+		let mut to_return = 0;
+		for market_id in market_ids {
+			if self.authorize(&account_id, market_id, 0).is_err() { continue }
+			let market = match self.active_markets.get_mut(&market_id) {
+				Some(market) => market,
+				None => continue
+			};
+
+			let (returned, unfilled_orders) = market.cancel_all_orders_for(&account_id, outcome);
+			to_return += returned;
+			if unfilled_orders > 0 {
+				self.release_storage(&account_id, Some(market_id), unfilled_orders * storage::ORDER_ENTRY_BYTES);
+			}
+		}
+
+		if to_return > 0 {
+			self.add_balance(to_return, account_id);
+		}
+		return to_return;
+	}
+
 	pub fn resolute_market(
 		&mut self, 
 		market_id: u64, 
-		winning_outcome: Option<u64>,
+		payout_numerator: Option<Vec<u128>>,
 		stake: u128
 	) {
+		self.assert_not_paused(market_id, Action::Reporting);
 		let account_id = env::predecessor_account_id();
 		let balance = self.get_fdai_balance(account_id.to_string());
         assert!(balance >= stake, "not enough balance to cover stake");
 		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
-		assert_eq!(market.resoluted, false);
+		let change = market.resolute(account_id.to_string(), payout_numerator, stake);
+		self.subtract_balance(stake - change, account_id.to_string());
+		self.use_storage(&account_id, Some(market_id), storage::STAKE_ENTRY_BYTES);
+		self.track_market_for(&account_id, market_id);
+		self.payout_creation_bond(market_id, account_id);
+	}
+
+	// Once resoluted the creation bond goes back to the creator if the designated reporter reported in time, otherwise it goes to the first open reporter
+	fn payout_creation_bond(
+		&mut self,
+		market_id: u64,
+		reporter: String
+	) {
+		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+		if !market.is_resoluted() || market.creation_bond == 0 { return }
 
+		let bond = market.creation_bond;
+		market.creation_bond = 0;
+		let receiver = if market.in_designated_reporting_window() { market.creator.to_string() } else { reporter };
+		self.add_balance(bond, receiver);
+	}
+
+	// Permissionless, the caller escrows the resolute bond which is staked on the oracle's outcome once it reports
+	pub fn request_resolution(
+		&mut self,
+		market_id: u64
+	) -> Promise {
+		self.assert_not_paused(market_id, Action::Reporting);
+		let account_id = env::predecessor_account_id();
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		assert_eq!(market.get_status(), MarketStatus::Reporting, "market isn't open for reporting");
+		assert!(market.pending_oracle_request.is_none(), "resolution has already been requested");
+		assert!(market.can_report(&account_id), "only the designated reporter can resolute during the designated reporting window");
+		let oracle = market.oracle.clone().expect("market has no oracle");
+		let bond = market.resolute_bond;
+		let balance = self.get_fdai_balance(account_id.to_string());
+		assert!(balance >= bond, "not enough balance to cover stake");
 
-		let change = market.resolute(winning_outcome, stake);
-		self.subtract_balance(stake - change);
+		let market = self.active_markets.get_mut(&market_id).unwrap();
+		market.pending_oracle_request = Some(account_id.to_string());
+		self.subtract_balance(bond, account_id.to_string());
+		// Charged upfront, the callback can't reject the requester anymore
+		self.use_storage(&account_id, Some(market_id), storage::STAKE_ENTRY_BYTES);
+		self.track_market_for(&account_id, market_id);
+
+		let gas = env::prepaid_gas() / 3;
+		return oracle::ext_oracle::get_outcome(oracle.request_id, &oracle.contract_id, 0, gas)
+		.then(oracle::ext_self::on_oracle_outcome(market_id, account_id, &env::current_account_id(), 0, gas));
+	}
+
+	pub fn on_oracle_outcome(
+		&mut self,
+		market_id: u64,
+		requester: String
+	) {
+		assert_eq!(env::predecessor_account_id(), env::current_account_id(), "only the contract can call this method");
+		let report = match env::promise_result(0) {
+			PromiseResult::Successful(result) => serde_json::from_slice::<Option<OracleReport>>(&result).unwrap_or(None),
+			_ => None
+		};
+		self.apply_oracle_report(market_id, requester, report);
+	}
+
+	// Stakes the reported outcome as the initial resolution, the requester's bond is refunded if there's nothing to stake on
+	fn apply_oracle_report(
+		&mut self,
+		market_id: u64,
+		requester: String,
+		report: Option<OracleReport>
+	) {
+		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+		assert_eq!(market.pending_oracle_request, Some(requester.to_string()), "no pending oracle request for this account");
+		market.pending_oracle_request = None;
+		let bond = market.resolute_bond;
+
+		let to_return = match report {
+			Some(report) if market.get_status() == MarketStatus::Reporting && market.can_report(&requester) && market.is_valid_payout(&report.payout_numerator) => {
+				market.resolute(requester.to_string(), report.payout_numerator, bond)
+			},
+			_ => bond
+		};
+
+		self.add_balance(to_return, requester.to_string());
+		self.payout_creation_bond(market_id, requester);
 	}
 
 	pub fn withdraw_dispute_stake(
 		&mut self, 
 		market_id: u64,
 		dispute_round: u64,
-		outcome: Option<u64>
+		outcome: Option<Vec<u128>>
 	) {
+		let account_id = env::predecessor_account_id();
 		let market = self.active_markets.get_mut(&market_id).expect("invalid market");
 		let to_return = market.cancel_dispute_participation(dispute_round, outcome);
-		self.add_balance(to_return, env::predecessor_account_id());
+		// Claiming already released everything the account used in the market so never release more than what's left
+		let bytes_used = *market.storage_by_account.get(&account_id).unwrap_or(&0);
+		let bytes = if to_return > 0 { std::cmp::min(bytes_used, storage::STAKE_ENTRY_BYTES) } else { 0 };
+		self.add_balance(to_return, account_id.to_string());
+		if bytes > 0 {
+			self.release_storage(&account_id, Some(market_id), bytes);
+		}
 	}
 
 	pub fn dispute_market(
 		&mut self, 
 		market_id: u64, 
-		winning_outcome: Option<u64>,
+		payout_numerator: Option<Vec<u128>>,
 		stake: u128
 	) {
+		self.assert_not_paused(market_id, Action::Reporting);
 	    let account_id = env::predecessor_account_id();
         let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
 		let balance = self.fdai_balances.get(&account_id).unwrap_or(&0);
 		assert!(balance >= &stake, "not enough balance to cover stake");
-		let change = market.dispute(winning_outcome, stake);
-        self.subtract_balance(stake - change);
+		let change = market.dispute(payout_numerator, stake);
+        self.subtract_balance(stake - change, account_id.to_string());
+		self.use_storage(&account_id, Some(market_id), storage::STAKE_ENTRY_BYTES);
+		self.track_market_for(&account_id, market_id);
 	}
 
 	pub fn finalize_market(
 		&mut self, 
 		market_id: u64, 
-		winning_outcome: Option<u64>
+		payout_numerator: Option<Vec<u128>>
 	) {
-		let market = self.active_markets.get_mut(&market_id).unwrap();
-		assert_eq!(market.resoluted, true);
-		if market.disputed {
-			assert_eq!(env::predecessor_account_id(), self.creator, "only the judge can resolute disputed markets");
-		} else {
-			// Check that the first dispute window is closed
-			let dispute_window = market.resolution_windows.last().expect("no dispute window found, something went wrong");
-			assert!(env::block_timestamp() / 1000000 >= dispute_window.end_time || dispute_window.round == 2, "dispute window still open")
+		self.assert_not_paused(market_id, Action::Reporting);
+		let is_judge = self.caller_has_role(Role::Judge);
+		let judge_threshold = self.judge_threshold;
+		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+		match market.get_status() {
+			MarketStatus::AwaitingJudge => {
+				assert!(is_judge, "only the judge can resolute disputed markets");
+				let judge_id = env::predecessor_account_id();
+				let votes = market.vote_as_judge(judge_id.to_string(), payout_numerator.clone());
+				events::log_event("judge_voted", serde_json::json!({ "market_id": market_id, "judge": judge_id, "payout_numerator": payout_numerator, "votes": votes }));
+				if votes < judge_threshold { return }
+			},
+			MarketStatus::Disputing(_) => {
+				// Check that the dispute window is closed
+				let dispute_window = market.resolution_windows.last().expect("no dispute window found, something went wrong");
+				assert!(time::now_ms() >= dispute_window.end_time, "dispute window still open");
+			},
+			MarketStatus::Finalized(_) => panic!("market is already finalized"),
+			_ => panic!("market isn't resoluted yet")
 		}
 
-        market.finalize(winning_outcome);
+        market.finalize(payout_numerator);
+	}
+
+	// NEP-145, attaches the deposit to account_id or the caller if none is given
+	pub fn storage_deposit(
+		&mut self,
+		account_id: Option<String>
+	) -> StorageBalance {
+		let amount = env::attached_deposit();
+		assert!(amount > 0, "requires attached deposit");
+		let account_id = account_id.unwrap_or(env::predecessor_account_id());
+		// NEP-145, registering has to cover at least the account's own balance entry, top ups can be any amount
+		if !self.storage_accounts.contains_key(&account_id) {
+			assert!(amount >= self.storage_balance_bounds().min, "attached deposit is less than the minimum storage balance");
+		}
+		let storage_account = self.storage_accounts.entry(account_id.to_string()).or_insert(StorageAccount::default());
+		storage_account.deposit += amount;
+		return self.storage_balance_of(account_id).unwrap();
+	}
+
+	// NEP-145, withdraws everything that isn't locked for used storage if no amount is given
+	pub fn storage_withdraw(
+		&mut self,
+		amount: Option<u128>
+	) -> StorageBalance {
+		// NEP-145, the yoctoNEAR makes sure the call is signed with a full access key
+		assert_eq!(env::attached_deposit(), 1, "requires attached deposit of exactly 1 yoctoNEAR");
+		let account_id = env::predecessor_account_id();
+		let storage_account = self.storage_accounts.get_mut(&account_id).expect("account has no storage deposit");
+		let available = storage_account.deposit - storage_account.locked();
+		let amount = amount.unwrap_or(available);
+		assert!(amount <= available, "amount exceeds available storage balance");
+		storage_account.deposit -= amount;
+		if amount > 0 {
+			Promise::new(account_id.to_string()).transfer(amount);
+		}
+		return self.storage_balance_of(account_id).unwrap();
+	}
+
+	pub fn storage_balance_of(
+		&self,
+		account_id: String
+	) -> Option<StorageBalance> {
+		return self.storage_accounts.get(&account_id).map(|storage_account| StorageBalance {
+			total: storage_account.deposit,
+			available: storage_account.deposit - storage_account.locked(),
+		});
+	}
+
+	pub fn storage_balance_bounds(
+		&self
+	) -> StorageBalanceBounds {
+		return StorageBalanceBounds {
+			min: storage::BALANCE_ENTRY_BYTES as u128 * storage::STORAGE_PRICE_PER_BYTE,
+			max: None,
+		};
+	}
+
+	// Charges the account's storage deposit, bytes used in a market are tracked on the market so they can be released on claim
+	fn use_storage(
+		&mut self,
+		account_id: &String,
+		market_id: Option<u64>,
+		bytes: u64
+	) {
+		let storage_account = self.storage_accounts.get_mut(account_id).expect("account has no storage deposit");
+		storage_account.bytes_used += bytes;
+		assert!(storage_account.locked() <= storage_account.deposit, "insufficient storage deposit");
+
+		if let Some(market_id) = market_id {
+			let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+			*market.storage_by_account.entry(account_id.to_string()).or_insert(0) += bytes;
+		}
+	}
+
+	fn release_storage(
+		&mut self,
+		account_id: &String,
+		market_id: Option<u64>,
+		bytes: u64
+	) {
+		if let Some(storage_account) = self.storage_accounts.get_mut(account_id) {
+			storage_account.bytes_used = storage_account.bytes_used.saturating_sub(bytes);
+		}
+
+		if let Some(market_id) = market_id {
+			let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
+			let market_bytes = market.storage_by_account.entry(account_id.to_string()).or_insert(0);
+			*market_bytes = market_bytes.saturating_sub(bytes);
+		}
 	}
 
 	fn subtract_balance(
 		&mut self, 
-		amount: u128
+		amount: u128,
+		account_id: String
 	) {
-		let account_id = env::predecessor_account_id();
 		let balance = self.fdai_balances.get(&account_id).unwrap();
 		assert!(*balance >= amount, "sender has unsufficient balance");
 		let new_balance = *balance - amount;
@@ -226,7 +1166,7 @@ impl Markets {
 		amount: u128,
 		account_id: String
 	) {
-		let balance = self.fdai_balances.get(&account_id).unwrap();
+		let balance = self.fdai_balances.get(&account_id).unwrap_or(&0);
 		let new_balance = *balance + amount;
 		self.fdai_balances.insert(account_id, new_balance);
 
@@ -240,7 +1180,7 @@ impl Markets {
 		market_id: u64
 	) -> Option<&ResolutionWindow> {
 		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
-		if !market.resoluted {
+		if !market.is_resoluted() {
 			return None;
 		}
 		return Some(market.resolution_windows.last().expect("invalid dispute window"));
@@ -272,19 +1212,22 @@ impl Markets {
 		market_id: u64, 
 		account_id: String
 	) -> u128 {
-		return self.active_markets.get(&market_id).unwrap().get_claimable_for(account_id);
+		let market = self.active_markets.get(&market_id).unwrap();
+		if market.is_voided() { return market.get_refund_for(&account_id) }
+		return market.get_claimable_for(account_id);
 	}
 
 	pub fn claim_creator_fee(
 		&mut self,
 		market_id: u64
 	) {
+		self.assert_not_paused(market_id, Action::Claiming);
 		let market = self.active_markets.get_mut(&market_id).expect("market doesn't exist");
 		let creator = market.creator.to_string();
+		assert!(!market.is_voided(), "market is voided");
 		assert_eq!(market.fee_claimed, false, "creator already claimed fees");
 		assert_eq!(env::predecessor_account_id(), creator.to_string(), "only creator himself can claim the fees");
-		// TODO: liquidity, as it is now is not the right metric, filled volume would be
-		let fee_payout = market.liquidity * market.fee_percentage / 100;
+		let fee_payout = market.get_creator_fee();
 		market.fee_claimed = true;
 		self.add_balance(fee_payout, creator.to_string());
 	}
@@ -294,31 +1237,76 @@ impl Markets {
 		market_id: u64, 
 		account_id: String
 	) {
-		let market = self.active_markets.get_mut(&market_id).unwrap();
-		assert!(env::block_timestamp() / 1000000 >= market.end_time, "market hasn't ended yet");
-		assert_eq!(market.resoluted, true);
-		assert_eq!(market.finalized, true);
+		if let Err(error) = self.try_claim_earnings(market_id, account_id) {
+			panic!("{}", error);
+		}
+	}
 
-		let claimable = market.get_claimable_for(account_id.to_string());
-		market.reset_balances_for(account_id.to_string());
-		market.delete_resolution_for(account_id.to_string());
+	// Claims every finalized market in market_ids, markets that can't be claimed yet are skipped instead of failing the whole call
+	pub fn claim_all(
+		&mut self,
+		account_id: String,
+		market_ids: Vec<u64>
+	) -> Vec<ClaimResult> {
+		return market_ids.into_iter().map(|market_id| {
+			match self.try_claim_earnings(market_id, account_id.to_string()) {
+				Ok(claimed) => ClaimResult { market_id, claimed, error: None },
+				Err(error) => ClaimResult { market_id, claimed: 0, error: Some(error) }
+			}
+		}).collect();
+	}
 
-		self.add_balance(claimable, account_id);
+	fn try_claim_earnings(
+		&mut self,
+		market_id: u64,
+		account_id: String
+	) -> Result<u128, String> {
+		self.authorize(&account_id, market_id, 0)?;
+		if self.is_paused(market_id, Action::Claiming) { return Err("claiming is paused".to_string()) }
+		let market = match self.active_markets.get_mut(&market_id) {
+			Some(market) => market,
+			None => return Err("market doesn't exist".to_string())
+		};
+		let claimable = if market.is_voided() {
+			let refund = market.get_refund_for(&account_id);
+			market.reset_balances_for(account_id.to_string());
+			market.clear_stakes_for(&account_id);
+			refund
+		} else if market.is_finalized() {
+			let claimable = market.get_claimable_for(account_id.to_string());
+			market.reset_balances_for(account_id.to_string());
+			market.delete_resolution_for(account_id.to_string());
+			claimable
+		} else {
+			return Err("market isn't finalized yet".to_string());
+		};
+		let bytes_used = market.storage_by_account.remove(&account_id).unwrap_or(0);
+
+		self.add_balance(claimable, account_id.to_string());
+		self.release_storage(&account_id, None, bytes_used);
+		if let Some(market_ids) = self.markets_by_account.get_mut(&account_id) {
+			market_ids.retain(|id| id != &market_id);
+		}
+		return Ok(claimable);
 	}
 
 	pub fn get_all_markets(
 		&self
-	) -> &BTreeMap<u64, Market> {
-		return &self.active_markets;
+	) -> BTreeMap<u64, Market> {
+		let mut markets = BTreeMap::new();
+		for (market_id, market) in self.active_markets.iter() {
+			markets.insert(*market_id, market.with_live_status());
+		}
+		return markets;
 	}
 
 	pub fn get_markets_by_id(
 		&self, 
 		market_ids: Vec<u64>
-	) -> BTreeMap<u64, &Market> {
+	) -> BTreeMap<u64, Market> {
 		let mut markets = BTreeMap::new();
 		for market_id in market_ids {
-			markets.insert(market_id, self.active_markets.get(&market_id).unwrap());
+			markets.insert(market_id, self.active_markets.get(&market_id).unwrap().with_live_status());
 		}
 		return markets;
 	}
@@ -326,10 +1314,41 @@ impl Markets {
 	pub fn get_specific_markets(
 		&self, 
 		market_ids: Vec<u64>
-	) -> BTreeMap<u64, &Market> {
+	) -> BTreeMap<u64, Market> {
 		let mut markets = BTreeMap::new();
-		for market_id in 0..market_ids.len() {
-			markets.insert(market_id as u64, self.active_markets.get(&(market_id as u64)).unwrap());
+		for market_id in market_ids {
+			if let Some(market) = self.active_markets.get(&market_id) {
+				markets.insert(market_id, market.with_live_status());
+			}
+		}
+		return markets;
+	}
+
+	// Returns up to limit market summaries with an id >= from_index, category and creator filters are served from their index
+	pub fn get_markets(
+		&self,
+		from_index: u64,
+		limit: u64,
+		filter: Option<MarketFilter>
+	) -> Vec<MarketSummary> {
+		let filter = filter.unwrap_or(MarketFilter::default());
+		let empty_vec: Vec<u64> = vec![];
+		let candidates: Vec<u64> = if let Some(category) = &filter.category {
+			self.markets_by_category.get(category).unwrap_or(&empty_vec).iter().filter(|id| **id >= from_index).cloned().collect()
+		} else if let Some(creator) = &filter.creator {
+			self.markets_by_creator.get(creator).unwrap_or(&empty_vec).iter().filter(|id| **id >= from_index).cloned().collect()
+		} else {
+			self.active_markets.range(from_index..).map(|(id, _)| *id).collect()
+		};
+
+		let mut markets = vec![];
+		for market_id in candidates {
+			if markets.len() as u64 >= limit { break }
+			if let Some(market) = self.active_markets.get(&market_id) {
+				if filter.matches(market) {
+					markets.push(MarketSummary::from_market(market));
+				}
+			}
 		}
 		return markets;
 	}
@@ -357,12 +1376,109 @@ impl Markets {
 		return orderbook.get_liquidity_at_price(price);
 	}
 
+	// Aggregated bids for an outcome, best (highest) price first
+	pub fn get_orderbook(
+		&self,
+		market_id: u64,
+		outcome: u64,
+		levels: u64
+	) -> Vec<PriceLevel> {
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		let orderbook = market.orderbooks.get(&outcome).expect("outcome doesn't exist");
+		return orderbook.get_price_levels(levels);
+	}
+
+	// Asks for an outcome implied by the other outcomes' bids, best (lowest) price first
+	pub fn get_implied_book(
+		&self,
+		market_id: u64,
+		outcome: u64,
+		levels: u64
+	) -> Vec<PriceLevel> {
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		assert!(outcome < market.outcomes, "outcome doesn't exist");
+		return market.get_implied_book(outcome, levels);
+	}
+
+	// Cumulative shares and spend that can be bought per implied price, across all inverse orderbooks
+	pub fn get_depth_curve(
+		&self,
+		market_id: u64,
+		outcome: u64
+	) -> Vec<DepthPoint> {
+		return depth::depth_curve(&self.get_implied_book(market_id, outcome, u64::max_value()));
+	}
+
+	// What a market order spending up to spend would get right now
+	pub fn quote(
+		&self,
+		market_id: u64,
+		outcome: u64,
+		spend: u128
+	) -> Quote {
+		return depth::quote(&self.get_implied_book(market_id, outcome, u64::max_value()), spend);
+	}
+
+	// Retained trades numbered from onwards, oldest first
+	pub fn get_trades(
+		&self,
+		market_id: u64,
+		from: u64,
+		limit: u64
+	) -> Vec<&Trade> {
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		return market.trade_history.get_trades(from, limit);
+	}
+
+	// Interval in ms, candles without trades are left out
+	pub fn get_candles(
+		&self,
+		market_id: u64,
+		outcome: u64,
+		interval: u64
+	) -> Vec<Candle> {
+		assert!(interval > 0, "interval should be larger than 0");
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		return market.trade_history.get_candles(outcome, interval);
+	}
+
+	// Positions in every unclaimed market the account traded or staked in, archived markets are left out
+	pub fn get_portfolio(
+		&self,
+		account_id: String
+	) -> Vec<PortfolioEntry> {
+		let market_ids = match self.markets_by_account.get(&account_id) {
+			Some(market_ids) => market_ids,
+			None => return vec![]
+		};
+		return market_ids.iter()
+		.filter_map(|market_id| self.active_markets.get(market_id))
+		.map(|market| PortfolioEntry::from_market(market, &account_id))
+		.collect();
+	}
+
 	pub fn get_market(
 		&self, 
 		id: u64
-	) -> &Market {
+	) -> Market {
 		let market = self.active_markets.get(&id);
-		return market.unwrap();
+		return market.unwrap().with_live_status();
+	}
+
+	pub fn get_market_status(
+		&self,
+		market_id: u64
+	) -> MarketStatus {
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		return market.get_status();
+	}
+
+	pub fn get_market_timeline(
+		&self,
+		market_id: u64
+	) -> MarketTimeline {
+		let market = self.active_markets.get(&market_id).expect("market doesn't exist");
+		return market.get_timeline();
 	}
 
 	pub fn get_owner(
@@ -399,6 +1515,7 @@ impl Markets {
 impl Default for Markets {
 	fn default() -> Self {
 		Self {
+			state_version: migration::STATE_VERSION,
 			creator: "flux-dev".to_string(),
 			active_markets: BTreeMap::new(),
 			nonce: 0,
@@ -409,6 +1526,26 @@ impl Default for Markets {
 			user_count: 0,
 			max_fee_percentage: 5,
 			creation_bond: 0,
+			default_window_config: WindowConfig::default(),
+			designated_reporter_bond: 0,
+			markets_by_category: HashMap::new(),
+			markets_by_creator: HashMap::new(),
+			archived_markets: BTreeMap::new(),
+			treasury: "flux-dev".to_string(),
+			claim_period: 30 * 24 * time::HOUR,
+			storage_accounts: HashMap::new(),
+			markets_by_account: HashMap::new(),
+			operators: HashMap::new(),
+			global_pause: PauseFlags::default(),
+			market_pauses: HashMap::new(),
+			circuit_breaker: CircuitBreaker::default(),
+			roles: roles::initial_roles("flux-dev".to_string()),
+			judge_threshold: 1,
+			moderation_enabled: false,
+			self_trade_prevention: SelfTradePrevention::default(),
+			order_limits: OrderLimits::default(),
+			allowed_categories: vec!["sports", "crypto", "politics", "finance", "entertainment", "science", "weather", "other"].into_iter().map(|category| category.to_string()).collect(),
+			allowed_tags: vec![],
 		}
 	}
 }
@@ -453,8 +1590,8 @@ mod tests {
 		number_of_outcomes: u64
 	) -> Vec<String> {
 		let mut outcomes: Vec<String> = vec![];
-		for _ in 0..number_of_outcomes {
-			outcomes.push(empty_string());
+		for i in 0..number_of_outcomes {
+			outcomes.push(format!("outcome {}", i));
 		}
 		return outcomes;
 	}
@@ -498,6 +1635,30 @@ mod tests {
 		}
 	}
 
+	fn storage_deposit_amount() -> u128 {
+		return 10u128.pow(24);
+	}
+
+	// Deposits storage for the account and switches back to a context without an attached deposit
+	fn register_storage(
+		contract: &mut Markets,
+		account_id: String,
+		block_timestamp: u64
+	) {
+		let mut context = get_context(account_id.to_string(), block_timestamp);
+		context.attached_deposit = storage_deposit_amount();
+		testing_env!(context);
+		contract.storage_deposit(None);
+		testing_env!(get_context(account_id, block_timestamp));
+	}
+
+	// Most tests trade a single account against both sides of a market
+	fn allow_self_trades(
+		contract: &mut Markets
+	) {
+		contract.self_trade_prevention = SelfTradePrevention::Allow;
+	}
+
 	mod init_tests;
 	mod market_order_tests;
 	mod binary_order_matching_tests;
@@ -506,4 +1667,28 @@ mod tests {
 	mod claim_earnings_tests;
 	mod market_dispute_tests;
 	mod market_resolution_tests;
+	mod mock_oracle;
+	mod oracle_resolution_tests;
+	mod designated_reporter_tests;
+	mod market_timeline_tests;
+	mod market_status_tests;
+	mod market_listing_tests;
+	mod archive_tests;
+	mod storage_tests;
+	mod orderbook_view_tests;
+	mod depth_curve_tests;
+	mod trade_history_tests;
+	mod portfolio_tests;
+	mod claim_all_tests;
+	mod operator_tests;
+	mod pause_tests;
+	mod migration_tests;
+	mod roles_tests;
+	mod void_market_tests;
+	mod moderation_tests;
+	mod metadata_tests;
+	mod cancel_all_orders_tests;
+	mod expired_order_tests;
+	mod limits_tests;
+	mod partial_execution_tests;
 }
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use borsh::{BorshDeserialize, BorshSerialize};
+use super::market::Market;
+
+// What's left of a market once it's archived, orderbooks and resolution windows are dropped
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug)]
+pub struct ArchivedMarket {
+	pub id: u64,
+	pub description: String,
+	pub creator: String,
+	pub outcomes: u64,
+	pub outcome_tags: Vec<String>,
+	pub categories: Vec<String>,
+	pub end_time: u64,
+	pub finalization_time: u64,
+	pub archive_time: u64,
+	pub payout_numerator: Option<Vec<u128>>,
+	pub liquidity: u128,
+	pub swept_to_treasury: u128,
+}
+
+impl ArchivedMarket {
+	pub fn from_market(
+		market: &Market,
+		archive_time: u64,
+		swept_to_treasury: u128
+	) -> Self {
+		Self {
+			id: market.id,
+			description: market.description.to_string(),
+			creator: market.creator.to_string(),
+			outcomes: market.outcomes,
+			outcome_tags: market.outcome_tags.clone(),
+			categories: market.categories.clone(),
+			end_time: market.end_time,
+			finalization_time: market.finalization_time.expect("market isn't finalized yet"),
+			archive_time,
+			payout_numerator: market.payout_numerator.clone(),
+			liquidity: market.liquidity,
+			swept_to_treasury,
+		}
+	}
+}
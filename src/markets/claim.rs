@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+// Result of claiming a single market through claim_all, error holds why the market was skipped
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClaimResult {
+	pub market_id: u64,
+	pub claimed: u128,
+	pub error: Option<String>,
+}
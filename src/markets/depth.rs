@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use super::market::orderbook::PriceLevel;
+
+// Cumulative fill up to and including price
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DepthPoint {
+	pub price: u128,
+	pub shares: u128,
+	pub spend: u128,
+}
+
+// Expected fill for a market order, spend is what's actually used after rounding down to whole shares
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Quote {
+	pub shares: u128,
+	pub spend: u128,
+	pub average_price: u128,
+	pub worst_price: u128,
+}
+
+// Expects implied levels in the order a fill takes them, which is best (lowest) price first since bids fill from the highest price down
+pub fn depth_curve(
+	levels: &Vec<PriceLevel>
+) -> Vec<DepthPoint> {
+	let mut shares = 0;
+	let mut spend = 0;
+	return levels.iter().map(|level| {
+		shares += level.shares;
+		spend += level.shares * level.price;
+		DepthPoint { price: level.price, shares, spend }
+	}).collect();
+}
+
+pub fn quote(
+	levels: &Vec<PriceLevel>,
+	max_spend: u128
+) -> Quote {
+	let mut shares = 0;
+	let mut spend = 0;
+	let mut worst_price = 0;
+	for level in levels {
+		if level.price == 0 { continue }
+		let affordable_shares = (max_spend - spend) / level.price;
+		let filling = std::cmp::min(level.shares, affordable_shares);
+		if filling == 0 { break }
+
+		shares += filling;
+		spend += filling * level.price;
+		worst_price = level.price;
+	}
+
+	let average_price = if shares > 0 { spend / shares } else { 0 };
+	return Quote { shares, spend, average_price, worst_price };
+}
@@ -0,0 +1,10 @@
+use serde_json::json;
+use near_sdk::env;
+
+// Events are logged as {"event": ..., "data": {...}} so indexers can pick them up
+pub fn log_event(
+	event: &str,
+	data: serde_json::Value
+) {
+	env::log(json!({ "event": event, "data": data }).to_string().as_bytes());
+}
@@ -0,0 +1,20 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+// Limits on orders placed by a single account in a single market, a max of 0 disables it
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+pub struct OrderLimits {
+	pub max_open_orders: u64,
+	pub max_position: u128, // Shares per outcome, filled and still open
+	pub min_order_size: u128, // Spend after rounding to the order's price
+}
+
+impl Default for OrderLimits {
+	fn default() -> Self {
+		Self {
+			max_open_orders: 100,
+			max_position: 0,
+			min_order_size: 100,
+		}
+	}
+}
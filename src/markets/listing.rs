@@ -0,0 +1,77 @@
+use std::mem;
+use serde::{Deserialize, Serialize};
+use super::market::{Market, MarketStatus};
+
+// All set fields have to match, status only compares the variant so Disputing(_) matches every round
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MarketFilter {
+	pub category: Option<String>,
+	pub creator: Option<String>,
+	pub status: Option<MarketStatus>,
+	pub end_time_from: Option<u64>,
+	pub end_time_to: Option<u64>,
+	pub outcomes: Option<u64>,
+}
+
+impl MarketFilter {
+	pub fn matches(
+		&self,
+		market: &Market
+	) -> bool {
+		if let Some(category) = &self.category {
+			if !market.categories.contains(category) { return false }
+		}
+		if let Some(creator) = &self.creator {
+			if &market.creator != creator { return false }
+		}
+		if let Some(status) = &self.status {
+			if mem::discriminant(status) != mem::discriminant(&market.get_status()) { return false }
+		}
+		if let Some(end_time_from) = self.end_time_from {
+			if market.end_time < end_time_from { return false }
+		}
+		if let Some(end_time_to) = self.end_time_to {
+			if market.end_time > end_time_to { return false }
+		}
+		if let Some(outcomes) = self.outcomes {
+			if market.outcomes != outcomes { return false }
+		}
+		return true;
+	}
+}
+
+// Light weight version of a market without orderbooks or resolution windows
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MarketSummary {
+	pub id: u64,
+	pub description: String,
+	pub creator: String,
+	pub outcomes: u64,
+	pub outcome_tags: Vec<String>,
+	pub categories: Vec<String>,
+	pub creation_time: u64,
+	pub end_time: u64,
+	pub status: MarketStatus,
+	pub liquidity: u128,
+	pub fee_percentage: u128,
+}
+
+impl MarketSummary {
+	pub fn from_market(
+		market: &Market
+	) -> Self {
+		Self {
+			id: market.id,
+			description: market.description.to_string(),
+			creator: market.creator.to_string(),
+			outcomes: market.outcomes,
+			outcome_tags: market.outcome_tags.clone(),
+			categories: market.categories.clone(),
+			creation_time: market.creation_time,
+			end_time: market.end_time,
+			status: market.get_status(),
+			liquidity: market.liquidity,
+			fee_percentage: market.fee_percentage,
+		}
+	}
+}
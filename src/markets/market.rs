@@ -1,25 +1,45 @@
 use std::string::String;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use near_sdk::{near_bindgen, env};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug)]
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
 pub struct ResolutionWindow {
 	pub round: u64,
-	pub participants_to_outcome_to_stake: HashMap<String, HashMap<u64, u128>>, // Account to outcome to stake
+	pub participants_to_outcome_to_stake: HashMap<String, HashMap<String, u128>>, // Account to payout id to stake
 	pub required_bond_size: u128,
-	pub staked_per_outcome: HashMap<u64, u128>, // Staked per outcome
+	pub staked_per_outcome: HashMap<String, u128>, // Staked per payout id
 	pub end_time: u64,
-	pub outcome: Option<u64>,
+	pub outcome: Option<Vec<u128>>,
 }
 
+pub mod metadata;
+pub mod order_type;
 pub mod orderbook;
+pub mod self_trade;
+pub mod status;
+pub mod trades;
 type Orderbook = orderbook::Orderbook;
+pub type MarketStatus = status::MarketStatus;
+type MarketMetadata = metadata::MarketMetadata;
 type Order = orderbook::Order;
+type PriceLevel = orderbook::PriceLevel;
+type SelfTradePrevention = self_trade::SelfTradePrevention;
+type OrderType = order_type::OrderType;
+type Trade = trades::Trade;
+type TradeHistory = trades::TradeHistory;
+type OracleConfig = super::oracle::OracleConfig;
+type OrderLimits = super::limits::OrderLimits;
+type WindowConfig = super::time::WindowConfig;
+type MarketTimeline = super::time::MarketTimeline;
+use super::time;
+
+// Max iterations a single order matches for, each one fills a price level. What's left of the order rests or is refunded depending on its order type
+pub const MAX_MATCH_ITERATIONS: u64 = 50;
 
 #[near_bindgen]
-#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug)]
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
 pub struct Market {
 	pub id: u64,
 	pub description: String,
@@ -28,21 +48,32 @@ pub struct Market {
 	pub outcomes: u64,
 	pub outcome_tags: Vec<String>,
 	pub categories: Vec<String>,
+	pub metadata: MarketMetadata,
 	pub last_price_for_outcomes: HashMap<u64, u128>,
+	pub trade_history: TradeHistory,
 	pub creation_time: u64,
 	pub end_time: u64,
 	pub orderbooks: BTreeMap<u64, orderbook::Orderbook>,
-	pub winning_outcome: Option<u64>, // invalid has outcome id: self.outcomes
-	pub resoluted: bool,
+	pub payout_numerator: Option<Vec<u128>>, // payout per outcome summing to 100, None is invalid
+	pub status: MarketStatus,
+	pub finalization_time: Option<u64>,
 	pub resolute_bond: u128,
 	pub liquidity: u128,
-	pub disputed: bool,
-	pub finalized: bool,
 	pub fee_claimed: bool,
 	pub fee_percentage: u128,
 	pub cost_percentage: u128,
 	pub api_source: String,
-	pub resolution_windows: Vec<ResolutionWindow>
+	pub resolution_windows: Vec<ResolutionWindow>,
+	pub oracle: Option<OracleConfig>,
+	pub pending_oracle_request: Option<String>, // Account that escrowed the bond for the outstanding oracle request
+	pub designated_reporter: String,
+	pub designated_reporter_bond: u128,
+	pub window_config: WindowConfig,
+	pub creation_bond: u128,
+	pub storage_by_account: HashMap<String, u64>, // Storage bytes each account was charged for in this market, released on claim
+	pub judge_votes: HashMap<String, Option<Vec<u128>>>, // Judge to the payout it voted for while awaiting the judge
+	pub order_limits: Option<OrderLimits>, // Overrides the protocol's order limits
+	pub circuit_breaker_from: u64, // Number of the first trade the circuit breaker looks at, moved up whenever trading is unpaused
 }
 
 #[near_bindgen]
@@ -58,7 +89,13 @@ impl Market {
 		end_time: u64, 
 		fee_percentage: u128, 
 		cost_percentage: u128, 
-		api_source: String
+		api_source: String,
+		oracle: Option<OracleConfig>,
+		designated_reporter: String,
+		window_config: WindowConfig,
+		designated_reporter_bond: u128,
+		creation_bond: u128,
+		metadata: MarketMetadata
 	) -> Self {
 		let mut empty_orderbooks = BTreeMap::new();
 
@@ -84,80 +121,281 @@ impl Market {
 			outcomes,
 			outcome_tags,
 			categories,
+			metadata,
 			last_price_for_outcomes: HashMap::new(),
-			creation_time: env::block_timestamp() / 1000000,
+			trade_history: TradeHistory::default(),
+			creation_time: time::now_ms(),
 			end_time,
 			orderbooks: empty_orderbooks,
-			winning_outcome: None,
-			resoluted: false,
+			payout_numerator: None,
+			status: MarketStatus::Open,
+			finalization_time: None,
 			resolute_bond: 5 * base.pow(17),
 			liquidity: 0,
-			disputed: false,
-			finalized: false,
 			fee_claimed: false,
 			fee_percentage,
 			cost_percentage,
 			api_source,
-			resolution_windows: vec![base_resolution_window]
+			resolution_windows: vec![base_resolution_window],
+			oracle,
+			pending_oracle_request: None,
+			designated_reporter,
+			designated_reporter_bond,
+			window_config,
+			creation_bond,
+			storage_by_account: HashMap::new(),
+			judge_votes: HashMap::new(),
+			order_limits: None,
+			circuit_breaker_from: 0,
 		}
 	}
 
+	// Returns the spend of the order that was left unfilled and isn't placed, the spend refunded from resting orders of account_id, how many of those without fills were removed
+	// and whether the order was placed
 	pub fn create_order(
 		&mut self, 
 		account_id: String, 
 		outcome: u64, 
 		amt_of_shares: u128, 
 		spend: u128, 
-		price: u128
-	) {
+		price: u128,
+		self_trade_prevention: SelfTradePrevention,
+		order_type: OrderType,
+		limits: &OrderLimits
+	) -> (u128, u128, u64, bool) {
 		assert!(spend > 0);
 		assert!(price > 0 && price < 100);
-		assert_eq!(self.resoluted, false);
-		assert!(env::block_timestamp() / 1000000 < self.end_time);
-		let (spend_left, shares_filled) = self.fill_matches(outcome, spend, price);
+		assert_eq!(self.get_status(), MarketStatus::Open, "market isn't open for trading");
+		if limits.max_open_orders > 0 {
+			assert!(self.get_open_order_count_for(&account_id) < limits.max_open_orders, "too many open orders in this market");
+		}
+		if limits.max_position > 0 {
+			assert!(self.get_position_for(&account_id, outcome) + amt_of_shares <= limits.max_position, "position limit exceeded");
+		}
+		let (mut spend_left, shares_filled, mut cancelled, refunded, removed_orders) = self.fill_matches(&account_id, outcome, spend, price, self_trade_prevention);
+		// Matching stopped at MAX_MATCH_ITERATIONS, what's left would cross the book if it rested so it's refunded
+		let crossing = spend_left > 100 && self.get_market_price_for(outcome) <= price;
+		if order_type == OrderType::ImmediateOrCancel || crossing {
+			cancelled += spend_left;
+			spend_left = 0;
+		}
+		// Cancelled spend is taken off the order itself
+		let spend = spend - cancelled;
+		let amt_of_shares = if cancelled > 0 { shares_filled + spend_left / price } else { amt_of_shares };
 		let total_spend = spend - spend_left;
 		self.liquidity += shares_filled * 100;
 		let shares_filled = shares_filled;
-		let orderbook = self.orderbooks.get_mut(&outcome).unwrap();
-		orderbook.place_order(account_id, outcome, spend, amt_of_shares, price, total_spend, shares_filled);
+		// Nothing to place when self trade prevention or immediate or cancel cancelled all of it
+		let placed = spend > 0;
+		if placed {
+			let orderbook = self.orderbooks.get_mut(&outcome).unwrap();
+			orderbook.place_order(account_id, outcome, spend, amt_of_shares, price, total_spend, shares_filled);
+		}
+		return (cancelled, refunded, removed_orders, placed);
 	}
 
+	// Returns the spend left, shares filled, spend cancelled off the incoming order, spend refunded from resting orders of account_id and how many of those without fills were removed
 	fn fill_matches(
 		&mut self, 
+		account_id: &String,
 		outcome: u64, 
 		spend: u128, 
-		price: u128
-	) -> (u128, u128) {
-		let mut market_price = self.get_market_price_for(outcome);
-		if market_price > price { return (spend,0) }
+		price: u128,
+		self_trade_prevention: SelfTradePrevention
+	) -> (u128, u128, u128, u128, u64) {
 		let orderbook_ids = self.get_inverse_orderbook_ids(outcome);
+		let mut best_prices = self.get_best_prices_of(&orderbook_ids);
+		let mut market_price = to_market_price(&best_prices);
+		if market_price > price { return (spend, 0, 0, 0, 0) }
 
 		let mut shares_filled = 0;
 		let mut spendable = spend;
+		let mut cancelled = 0;
+		let mut refunded = 0;
+		let mut removed_orders = 0;
+		let mut iterations = 0;
+
+		while spendable > 100 && market_price <= price && iterations < MAX_MATCH_ITERATIONS {
+			iterations += 1;
+			let own_orders = match self_trade_prevention {
+				SelfTradePrevention::Allow => vec![],
+				_ => self.get_best_orders_of(account_id, &orderbook_ids)
+			};
+			if own_orders.len() > 0 {
+				match self_trade_prevention {
+					SelfTradePrevention::Allow => {},
+					SelfTradePrevention::CancelNewest => {
+						cancelled += spendable;
+						spendable = 0;
+						break;
+					},
+					SelfTradePrevention::CancelOldest => {
+						for (orderbook_id, order_id) in own_orders {
+							let (freed, removed) = self.decrement_order(orderbook_id, order_id, None);
+							refunded += freed;
+							if removed { removed_orders += 1 }
+						}
+					},
+					SelfTradePrevention::DecrementBoth => {
+						// A match takes the same shares from every book, so only the shares the account has in each of its books overlap
+						let mut own_shares_by_book: BTreeMap<u64, u128> = BTreeMap::new();
+						for (orderbook_id, order_id) in &own_orders {
+							let order = self.orderbooks.get(orderbook_id).unwrap().open_orders.get(order_id).unwrap();
+							*own_shares_by_book.entry(*orderbook_id).or_insert(0) += order.amt_of_shares - order.shares_filled;
+						}
+						let own_shares = *own_shares_by_book.values().min().unwrap();
+						let shares_to_decrement = std::cmp::min(spendable / market_price, own_shares);
+						if shares_to_decrement == 0 { break }
+						cancelled += shares_to_decrement * market_price;
+						spendable -= shares_to_decrement * market_price;
+
+						let mut shares_left_by_book: BTreeMap<u64, u128> = own_shares_by_book.keys().map(|orderbook_id| (*orderbook_id, shares_to_decrement)).collect();
+						for (orderbook_id, order_id) in own_orders {
+							let shares_left = shares_left_by_book.get_mut(&orderbook_id).unwrap();
+							if *shares_left == 0 { continue }
+							let order = self.orderbooks.get(&orderbook_id).unwrap().open_orders.get(&order_id).unwrap();
+							let shares = std::cmp::min(order.amt_of_shares - order.shares_filled, *shares_left);
+							let (freed, removed) = self.decrement_order(orderbook_id, order_id, Some(shares));
+							refunded += freed;
+							if removed { removed_orders += 1 }
+							*shares_left -= shares;
+						}
+					}
+				}
+				best_prices = self.get_best_prices_of(&orderbook_ids);
+				market_price = to_market_price(&best_prices);
+				continue;
+			}
 
-		while spendable > 100 && market_price <= price {
 			let mut shares_to_fill = spendable / market_price;
-			let shares_fillable = self.get_min_shares_fillable(outcome);
+			let shares_fillable = best_prices.iter()
+			.map(|(orderbook_id, best_price)| self.orderbooks.get(orderbook_id).unwrap().get_liquidity_at_price(*best_price))
+			.min()
+			.unwrap();
 			self.last_price_for_outcomes.insert(outcome, market_price);
 
 			if shares_fillable < shares_to_fill {
 				shares_to_fill = shares_fillable;
             }
-			for orderbook_id in &orderbook_ids {
-				let orderbook = self.orderbooks.get_mut(orderbook_id).unwrap();
-				if !orderbook.best_price.is_none() {
-					let best_price = orderbook.get_best_price();
-					self.last_price_for_outcomes.insert(*orderbook_id, best_price);
-					orderbook.fill_best_orders(shares_to_fill);
-				}
+			self.record_trade(outcome, market_price, shares_to_fill);
+			// Only the books that are filled can change their best price so those are the only ones updated
+			for (orderbook_id, best_price) in best_prices.clone() {
+				let orderbook = self.orderbooks.get_mut(&orderbook_id).unwrap();
+				self.last_price_for_outcomes.insert(orderbook_id, best_price);
+				orderbook.fill_best_orders(shares_to_fill);
+				match orderbook.best_price {
+					Some(next_best_price) => best_prices.insert(orderbook_id, next_best_price),
+					None => best_prices.remove(&orderbook_id)
+				};
+				self.record_trade(orderbook_id, best_price, shares_to_fill);
 			}
 
 			spendable -= shares_to_fill * market_price;
 			shares_filled += shares_to_fill;
-			market_price = self.get_market_price_for(outcome);
+			market_price = to_market_price(&best_prices);
+		}
+
+		return (spendable, shares_filled, cancelled, refunded, removed_orders);
+	}
+
+	// Best price of each of orderbook_ids that has one
+	fn get_best_prices_of(
+		&self,
+		orderbook_ids: &Vec<u64>
+	) -> BTreeMap<u64, u128> {
+		let mut best_prices = BTreeMap::new();
+		for orderbook_id in orderbook_ids {
+			if let Some(best_price) = self.orderbooks.get(orderbook_id).unwrap().best_price {
+				best_prices.insert(*orderbook_id, best_price);
+			}
+		}
+		return best_prices;
+	}
+
+	pub fn get_open_order_count_for(
+		&self,
+		account_id: &String
+	) -> u64 {
+		return self.orderbooks.values()
+		.map(|orderbook| orderbook.get_open_orders_of(account_id).len() as u64)
+		.sum();
+	}
+
+	// Shares of outcome account_id holds plus the shares its open orders can still buy
+	pub fn get_position_for(
+		&self,
+		account_id: &String,
+		outcome: u64
+	) -> u128 {
+		let orderbook = self.orderbooks.get(&outcome).expect("outcome doesn't exist");
+		let open_shares: u128 = orderbook.get_open_orders_of(account_id).iter()
+		.map(|order| order.amt_of_shares - order.shares_filled)
+		.sum();
+		return orderbook.get_shares_filled_for(account_id.to_string()) + open_shares;
+	}
+
+	// Open orders of account_id that the next fill against orderbook_ids would match, as (orderbook id, order id)
+	fn get_best_orders_of(
+		&self,
+		account_id: &String,
+		orderbook_ids: &Vec<u64>
+	) -> Vec<(u64, u128)> {
+		let mut orders = vec![];
+		for orderbook_id in orderbook_ids {
+			let orderbook = self.orderbooks.get(orderbook_id).unwrap();
+			if orderbook.best_price.is_none() { continue }
+			for order_id in orderbook.get_best_orders_of(account_id) {
+				orders.push((*orderbook_id, order_id));
+			}
 		}
+		return orders;
+	}
 
-		return (spendable, shares_filled);
+	// Takes shares, or everything if None, off a resting order. Returns the freed spend and whether an order without fills was removed
+	fn decrement_order(
+		&mut self,
+		orderbook_id: u64,
+		order_id: u128,
+		shares: Option<u128>
+	) -> (u128, bool) {
+		let orderbook = self.orderbooks.get_mut(&orderbook_id).unwrap();
+		let unfilled = orderbook.open_orders.get(&order_id).unwrap().shares_filled == 0;
+		let freed = match shares {
+			Some(shares) => orderbook.decrement_order(order_id, shares),
+			None => orderbook.remove_order(order_id)
+		};
+		return (freed, unfilled && !orderbook.open_orders.contains_key(&order_id));
+	}
+
+	fn record_trade(
+		&mut self,
+		outcome: u64,
+		price: u128,
+		shares: u128
+	) {
+		if shares == 0 { return }
+		self.trade_history.record(Trade {
+			time: time::now_ms(),
+			outcome,
+			price,
+			shares,
+		});
+	}
+
+	// Largest difference between the highest and lowest price an outcome traded at since from (ms)
+	// Trades are recorded in time order so the walk stops at the first trade before from
+	pub fn get_max_price_move_since(
+		&self,
+		from: u64
+	) -> u128 {
+		let mut price_ranges: HashMap<u64, (u128, u128)> = HashMap::new();
+		for trade in self.trade_history.iter_newest_first(self.circuit_breaker_from) {
+			if trade.time < from { break }
+			let range = price_ranges.entry(trade.outcome).or_insert((trade.price, trade.price));
+			range.0 = std::cmp::min(range.0, trade.price);
+			range.1 = std::cmp::max(range.1, trade.price);
+		}
+		return price_ranges.values().map(|(low, high)| high - low).max().unwrap_or(0);
 	}
 
 	pub fn get_min_shares_fillable(
@@ -205,6 +443,64 @@ impl Market {
 		return market_price;
 	}
 
+	// Synthetic asks for outcome made up of the other outcomes' bids, lowest price first. Buying at 100 - the sum of their best bids matches all of them at once,
+	// same as get_market_price_for empty orderbooks are left out and the book ends once one of the others runs out of bids
+	pub fn get_implied_book(
+		&self,
+		outcome: u64,
+		levels: u64
+	) -> Vec<PriceLevel> {
+		let inverse_books: Vec<Vec<PriceLevel>> = self.get_inverse_orderbook_ids(outcome).iter()
+		.map(|orderbook_id| self.orderbooks.get(orderbook_id).unwrap().get_price_levels(u64::max_value()))
+		.filter(|book_levels| !book_levels.is_empty())
+		.collect();
+
+		let mut implied_levels: Vec<PriceLevel> = vec![];
+		if inverse_books.is_empty() { return implied_levels }
+
+		// Per inverse book: index of the current level, shares left at it and whether its orders were counted yet
+		let mut pointers: Vec<(usize, u128, bool)> = inverse_books.iter().map(|book_levels| (0, book_levels[0].shares, false)).collect();
+
+		loop {
+			let price_sum: u128 = pointers.iter().enumerate().map(|(i, pointer)| inverse_books[i][pointer.0].price).sum();
+			let price = 100u128.saturating_sub(price_sum);
+			let shares = pointers.iter().map(|pointer| pointer.1).min().unwrap();
+			let mut orders = 0;
+			for (i, pointer) in pointers.iter_mut().enumerate() {
+				if !pointer.2 {
+					orders += inverse_books[i][pointer.0].orders;
+					pointer.2 = true;
+				}
+			}
+
+			match implied_levels.last_mut() {
+				Some(level) if level.price == price => {
+					level.shares += shares;
+					level.orders += orders;
+				},
+				_ => {
+					if implied_levels.len() as u64 == levels { break }
+					implied_levels.push(PriceLevel { price, shares, orders });
+				}
+			}
+
+			let mut book_exhausted = false;
+			for (i, pointer) in pointers.iter_mut().enumerate() {
+				pointer.1 -= shares;
+				if pointer.1 == 0 {
+					pointer.0 += 1;
+					match inverse_books[i].get(pointer.0) {
+						Some(level) => *pointer = (pointer.0, level.shares, false),
+						None => book_exhausted = true
+					}
+				}
+			}
+			if book_exhausted { break }
+		}
+
+		return implied_levels;
+	}
+
 	fn get_inverse_orderbook_ids(
 		&self, 
 		principle_outcome: u64
@@ -220,79 +516,256 @@ impl Market {
 		return orderbooks;
 	}
 
-	fn to_numerical_outcome(
+	// Payout vectors are used as stake keys, invalid has its own id
+	pub fn to_payout_id(
 		&self, 
-		outcome: Option<u64>, 
+		payout_numerator: &Option<Vec<u128>>, 
+	) -> String {
+		match payout_numerator {
+			Some(numerators) => numerators.iter().map(|numerator| numerator.to_string()).collect::<Vec<String>>().join(":"),
+			None => "invalid".to_string()
+		}
+	}
+
+	fn assert_valid_payout(
+		&self,
+		payout_numerator: &Option<Vec<u128>>
+	) {
+		if let Some(numerators) = payout_numerator {
+			assert_eq!(numerators.len() as u64, self.outcomes, "invalid payout numerator");
+			assert_eq!(numerators.iter().sum::<u128>(), 100, "payout numerator should sum to 100");
+		}
+	}
+
+	pub fn is_valid_payout(
+		&self,
+		payout_numerator: &Option<Vec<u128>>
+	) -> bool {
+		match payout_numerator {
+			Some(numerators) => numerators.len() as u64 == self.outcomes && numerators.iter().sum::<u128>() == 100,
+			None => true
+		}
+	}
+
+	pub fn reporting_start_time(
+		&self
+	) -> u64 {
+		return self.end_time + self.window_config.grace_period;
+	}
+
+	pub fn designated_reporting_end_time(
+		&self
 	) -> u64 {
-		return outcome.unwrap_or(self.outcomes);
+		return self.reporting_start_time() + self.window_config.designated_reporting_period;
+	}
+
+	pub fn in_designated_reporting_window(
+		&self
+	) -> bool {
+		return time::now_ms() < self.designated_reporting_end_time();
+	}
+
+	pub fn get_status(
+		&self
+	) -> MarketStatus {
+		if !self.status.is_time_based() { return self.status.clone() }
+
+		let now = time::now_ms();
+		if now < self.end_time {
+			return MarketStatus::Open;
+		} else if now < self.reporting_start_time() {
+			return MarketStatus::Closed;
+		}
+		return MarketStatus::Reporting;
+	}
+
+	// All status changes go through here, illegal moves are rejected
+	fn transition_to(
+		&mut self,
+		next: MarketStatus
+	) {
+		let current = self.get_status();
+		assert!(current.can_transition_to(&next), "illegal market status transition from {:?} to {:?}", current, next);
+		self.status = next;
+	}
+
+	pub fn is_resoluted(
+		&self
+	) -> bool {
+		return self.get_status().is_resoluted();
+	}
+
+	pub fn is_finalized(
+		&self
+	) -> bool {
+		return self.get_status().is_finalized();
+	}
+
+	pub fn is_voided(
+		&self
+	) -> bool {
+		return self.get_status() == MarketStatus::Voided;
+	}
+
+	// Removes the open orders of account_id, in every outcome if none is given. Returns the unfilled spend and how many orders had no fills at all
+	pub fn cancel_all_orders_for(
+		&mut self,
+		account_id: &String,
+		outcome: Option<u64>
+	) -> (u128, u64) {
+		let mut to_return = 0;
+		let mut unfilled_orders = 0;
+		for (outcome_id, orderbook) in self.orderbooks.iter_mut() {
+			if outcome.map_or(false, |outcome| outcome != *outcome_id) { continue }
+
+			let order_ids = orderbook.orders_by_user.get(account_id).cloned().unwrap_or(vec![]);
+			for order_id in order_ids {
+				let shares_filled = match orderbook.open_orders.get(&order_id) {
+					Some(order) => order.shares_filled,
+					None => continue
+				};
+				if shares_filled == 0 { unfilled_orders += 1 }
+				to_return += orderbook.remove_order(order_id);
+			}
+		}
+		return (to_return, unfilled_orders);
+	}
+
+	pub fn approve(
+		&mut self
+	) {
+		assert_eq!(self.get_status(), MarketStatus::Pending, "market isn't pending approval");
+		self.transition_to(MarketStatus::Open);
+	}
+
+	// Stops trading and reporting for good, everyone can claim back what they put in through get_refund_for
+	pub fn void(
+		&mut self
+	) {
+		match self.get_status() {
+			MarketStatus::Finalized(_) => panic!("market is already finalized"),
+			MarketStatus::Voided => panic!("market is already voided"),
+			_ => {}
+		}
+		self.transition_to(MarketStatus::Voided);
+	}
+
+	// Copy of the market with its current status filled in, used by views
+	pub fn with_live_status(
+		&self
+	) -> Market {
+		let mut market = self.clone();
+		market.status = self.get_status();
+		return market;
+	}
+
+	pub fn get_timeline(
+		&self
+	) -> MarketTimeline {
+		let active_window = self.resolution_windows.last().expect("no resolution window exists, something went wrong at creation");
+		let status = self.get_status();
+		let resolution_window_end_time = if status.is_resoluted() { Some(active_window.end_time) } else { None };
+
+		return MarketTimeline {
+			status,
+			now: time::now_ms(),
+			creation_time: self.creation_time,
+			end_time: self.end_time,
+			reporting_start_time: self.reporting_start_time(),
+			designated_reporting_end_time: self.designated_reporting_end_time(),
+			resolution_window_round: active_window.round,
+			resolution_window_end_time,
+		};
+	}
+
+	pub fn can_report(
+		&self,
+		account_id: &String
+	) -> bool {
+		return !self.in_designated_reporting_window() || account_id == &self.designated_reporter;
 	}
 
 	pub fn resolute(
 		&mut self, 
-		winning_outcome: Option<u64>, 
+		account_id: String,
+		payout_numerator: Option<Vec<u128>>, 
 		stake: u128 // should reimplement this
 	) -> u128 {
-		assert!(env::block_timestamp() / 1000000 >= self.end_time, "market hasn't ended yet");
-		assert_eq!(self.resoluted, false, "market is already resoluted");
-		assert_eq!(self.finalized, false, "market is already finalized");
-		assert!(winning_outcome == None || winning_outcome.unwrap() < self.outcomes, "invalid winning outcome");
-		let outcome_id = self.to_numerical_outcome(winning_outcome);
+		match self.get_status() {
+			MarketStatus::Reporting => {},
+			MarketStatus::Pending => panic!("market isn't approved yet"),
+			MarketStatus::Open => panic!("market hasn't ended yet"),
+			MarketStatus::Closed => panic!("reporting hasn't opened yet"),
+			MarketStatus::Finalized(_) => panic!("market is already finalized"),
+			MarketStatus::Voided => panic!("market is voided"),
+			_ => panic!("market is already resoluted")
+		}
+		assert!(self.can_report(&account_id), "only the designated reporter can resolute during the designated reporting window");
+		self.assert_valid_payout(&payout_numerator);
+		let outcome_id = self.to_payout_id(&payout_numerator);
+		let required_bond = if self.in_designated_reporting_window() { self.designated_reporter_bond } else { self.resolute_bond };
+		let dispute_window = self.window_config.dispute_window;
 		let resolution_window = self.resolution_windows.last_mut().expect("no resolute window exists, something went wrong at creation");
 		assert_eq!(resolution_window.round, 0, "can only resolute once");
 		
 		let mut to_return = 0;
+		let mut bond_filled = false;
 		let staked_on_outcome = resolution_window.staked_per_outcome.get(&outcome_id).unwrap_or(&0);
 
-		if stake + staked_on_outcome >= self.resolute_bond {
-			to_return = stake + staked_on_outcome - self.resolute_bond;
-			self.winning_outcome = winning_outcome;
-			self.resoluted = true;
+		if stake + staked_on_outcome >= required_bond {
+			bond_filled = true;
+			to_return = stake + staked_on_outcome - required_bond;
+			self.payout_numerator = payout_numerator.clone();
 		} 
 
 		resolution_window.participants_to_outcome_to_stake
-		.entry(env::predecessor_account_id())
+		.entry(account_id)
 		.or_insert(HashMap::new())
-		.entry(outcome_id)
+		.entry(outcome_id.to_string())
 		.and_modify(|staked| {*staked += stake - to_return})
-		.or_insert(stake);
+		.or_insert(stake - to_return);
 
 		resolution_window.staked_per_outcome
 		.entry(outcome_id)
 		.and_modify(|total_staked| {*total_staked += stake - to_return})
-		.or_insert(stake);
+		.or_insert(stake - to_return);
 		
-		if self.resoluted {
-			resolution_window.outcome = winning_outcome;
+		if bond_filled {
+			resolution_window.outcome = payout_numerator;
 			let new_resolution_window = ResolutionWindow {
 				round: resolution_window.round + 1,
 				participants_to_outcome_to_stake: HashMap::new(),
 				required_bond_size: resolution_window.required_bond_size * 2,
 				staked_per_outcome: HashMap::new(), // Staked per outcome
-				end_time: env::block_timestamp() / 1000000 + 1800000, // 30 nano minutes should be 30 minutes
+				end_time: time::now_ms() + dispute_window,
 				outcome: None,
 			};
 			self.resolution_windows.push(new_resolution_window);
+			self.transition_to(MarketStatus::Disputing(1));
 		} 
 
-
-
 		return to_return;
 	}
 
 	pub fn dispute(
 		&mut self, 
-		winning_outcome: Option<u64>,
+		payout_numerator: Option<Vec<u128>>,
 		stake: u128
 	) -> u128 {
-		assert_eq!(self.resoluted, true, "market isn't resoluted yet");
-		assert_eq!(self.finalized, false, "market is already finalized");
-        assert!(winning_outcome == None || winning_outcome.unwrap() < self.outcomes, "invalid winning outcome");
-        assert!(winning_outcome != self.winning_outcome, "same oucome as last resolution");
+		match self.get_status() {
+			MarketStatus::Disputing(_) | MarketStatus::AwaitingJudge => {},
+			MarketStatus::Finalized(_) => panic!("market is already finalized"),
+			MarketStatus::Voided => panic!("market is voided"),
+			_ => panic!("market isn't resoluted yet")
+		}
+		self.assert_valid_payout(&payout_numerator);
+        assert!(payout_numerator != self.payout_numerator, "same oucome as last resolution");
 	
-		let outcome_id = self.to_numerical_outcome(winning_outcome);
+		let outcome_id = self.to_payout_id(&payout_numerator);
+		let dispute_window = self.window_config.dispute_window;
 		let resolution_window = self.resolution_windows.last_mut().expect("Invalid dispute window unwrap");
 		assert_eq!(resolution_window.round, 1, "for this version, there's only 1 round of dispute");
-		assert!(env::block_timestamp() / 1000000 <= resolution_window.end_time, "dispute window is closed, market can be finalized");
+		assert!(time::now_ms() <= resolution_window.end_time, "dispute window is closed, market can be finalized");
 
 		let full_bond_size = resolution_window.required_bond_size;
 		let mut bond_filled = false;
@@ -302,86 +775,101 @@ impl Market {
 		if staked_on_outcome + stake >= full_bond_size  {
 			bond_filled = true;
 			to_return = staked_on_outcome + stake - full_bond_size;
-			self.disputed = true; // Only as long as Judge exists
-			self.winning_outcome = winning_outcome;
+			self.payout_numerator = payout_numerator.clone();
 		}
 
 		// Add to disputors stake
 		resolution_window.participants_to_outcome_to_stake
 		.entry(env::predecessor_account_id())
 		.or_insert(HashMap::new())
-		.entry(outcome_id)
+		.entry(outcome_id.to_string())
 		.and_modify(|staked| { *staked += stake - to_return })
-		.or_insert(stake);
+		.or_insert(stake - to_return);
 
 		// Add to total staked on outcome
 		resolution_window.staked_per_outcome
-		.entry(outcome_id)
+		.entry(outcome_id.to_string())
 		.and_modify(|total_staked| {*total_staked += stake - to_return})
-		.or_insert(stake);
+		.or_insert(stake - to_return);
 		
 		// Check if this order fills the bond
 		if bond_filled {
 			// Set last winning outcome
-			resolution_window.outcome = winning_outcome;
+			resolution_window.outcome = payout_numerator;
 
 			//
 			resolution_window.staked_per_outcome
 			.entry(outcome_id)
 			.and_modify(|total_staked| {*total_staked = full_bond_size})
-			.or_insert(stake);
+			.or_insert(full_bond_size);
 
 			let next_resolution_window = ResolutionWindow{
 				round: resolution_window.round + 1,
 				participants_to_outcome_to_stake: HashMap::new(),
 				required_bond_size: resolution_window.required_bond_size * 2,
 				staked_per_outcome: HashMap::new(), // Staked per outcome
-				end_time: env::block_timestamp() / 1000000 + 1800000,
+				end_time: time::now_ms() + dispute_window,
 				outcome: None,
 				// invalid: false
 			};
 
 			self.resolution_windows.push(next_resolution_window);
+			self.transition_to(MarketStatus::AwaitingJudge); // Only as long as Judge exists
 		}
 
 		return to_return;
 	}
 
+	// Replaces the judge's earlier vote, returns how many judges voted for this payout
+	pub fn vote_as_judge(
+		&mut self,
+		judge_id: String,
+		payout_numerator: Option<Vec<u128>>
+	) -> u64 {
+		self.assert_valid_payout(&payout_numerator);
+		self.judge_votes.insert(judge_id, payout_numerator.clone());
+		return self.judge_votes.values().filter(|vote| **vote == payout_numerator).count() as u64;
+	}
+
 	pub fn finalize(
 		&mut self, 
-		winning_outcome: Option<u64>
+		payout_numerator: Option<Vec<u128>>
 	) {
-		assert_eq!(self.resoluted, true, "market isn't resoluted yet");
-		assert!(winning_outcome == None || winning_outcome.unwrap() < self.outcomes, "invalid outcome");
+		self.assert_valid_payout(&payout_numerator);
 	
-	    if self.disputed {
-            self.winning_outcome = winning_outcome;
+	    if self.get_status() == MarketStatus::AwaitingJudge {
+            self.payout_numerator = payout_numerator;
 		}
 		
-	    self.finalized = true;
+	    self.transition_to(MarketStatus::Finalized(self.payout_numerator.clone()));
+		self.finalization_time = Some(time::now_ms());
 	}
 
 	pub fn get_claimable_for(
 		&self, 
 		account_id: String
 	) -> u128 {
-		let invalid = self.winning_outcome.is_none();
 		let mut claimable = 0;
 		
 		// Claiming payouts
-		if invalid {
-			for (_, orderbook) in self.orderbooks.iter() {
-			    let spent = orderbook.get_spend_by(account_id.to_string());
-				claimable += spent; // market creator forfits his fee when market resolutes to invalid
-			}
-		} else {
-			for (_, orderbook) in self.orderbooks.iter() {
-				claimable += orderbook.get_open_order_value_for(account_id.to_string());
+		match &self.payout_numerator {
+			None => {
+				for (_, orderbook) in self.orderbooks.iter() {
+					let spent = orderbook.get_spend_by(account_id.to_string());
+					claimable += spent; // market creator forfits his fee when market resolutes to invalid
+				}
+			},
+			Some(numerators) => {
+				let mut winning_value = 0;
+				for (outcome, orderbook) in self.orderbooks.iter() {
+					claimable += orderbook.get_open_order_value_for(account_id.to_string());
+					let numerator = numerators[*outcome as usize];
+					if numerator > 0 {
+						winning_value += orderbook.calc_claimable_amt(account_id.to_string()) * numerator / 100;
+					}
+				}
+				claimable += winning_value * (100-self.fee_percentage)/100;
 			}
-
-			let winning_orderbook = self.orderbooks.get(&self.winning_outcome.unwrap()).unwrap();
-			let winning_value = winning_orderbook.calc_claimable_amt(account_id.to_string());
-			claimable += winning_value * (100-self.fee_percentage)/100;
 		}
 
 		// Claiming Dispute Earnings
@@ -389,15 +877,129 @@ impl Market {
 		return claimable;
 	}
 
+	// Total staked by the account on any outcome, indexed by resolution round
+	pub fn get_stake_by_round_for(
+		&self,
+		account_id: &String
+	) -> Vec<u128> {
+		return self.resolution_windows.iter().map(|window| {
+			match window.participants_to_outcome_to_stake.get(account_id) {
+				Some(stakes) => stakes.values().sum(),
+				None => 0
+			}
+		}).collect();
+	}
+
+	// Stakes on outcomes that didn't get bonded in a round and didn't win, these can be withdrawn through cancel_dispute_participation
+	pub fn get_withdrawable_stake_for(
+		&self,
+		account_id: String
+	) -> u128 {
+		let winning_outcome_id = self.to_payout_id(&self.payout_numerator);
+		let mut withdrawable = 0;
+		for window in &self.resolution_windows {
+			let bonded_outcome_id = self.to_payout_id(&window.outcome);
+			if let Some(stakes) = window.participants_to_outcome_to_stake.get(&account_id) {
+				for (outcome_id, stake) in stakes {
+					if outcome_id != &bonded_outcome_id && outcome_id != &winning_outcome_id {
+						withdrawable += stake;
+					}
+				}
+			}
+		}
+		return withdrawable;
+	}
+
+	pub fn get_creator_fee(
+		&self
+	) -> u128 {
+		// TODO: liquidity, as it is now is not the right metric, filled volume would be
+		return self.liquidity * self.fee_percentage / 100;
+	}
+
+	// Every account that traded or staked in this market
+	pub fn get_participants(
+		&self
+	) -> HashSet<String> {
+		let mut participants = HashSet::new();
+		for orderbook in self.orderbooks.values() {
+			for account_id in orderbook.spend_by_user.keys() {
+				participants.insert(account_id.to_string());
+			}
+			for account_id in orderbook.orders_by_user.keys() {
+				participants.insert(account_id.to_string());
+			}
+		}
+		for window in &self.resolution_windows {
+			for account_id in window.participants_to_outcome_to_stake.keys() {
+				participants.insert(account_id.to_string());
+			}
+		}
+		return participants;
+	}
+
+	// Total of everything participants and the creator could still claim or withdraw
+	pub fn get_unclaimed_total(
+		&self
+	) -> u128 {
+		let mut unclaimed = 0;
+		for account_id in self.get_participants() {
+			unclaimed += self.get_claimable_for(account_id.to_string());
+			unclaimed += self.get_withdrawable_stake_for(account_id);
+		}
+		if !self.fee_claimed {
+			unclaimed += self.get_creator_fee();
+		}
+		return unclaimed;
+	}
+
+	// What the account put into the market: spend on orders that hasn't been cancelled plus all stakes that haven't been withdrawn
+	pub fn get_refund_for(
+		&self,
+		account_id: &String
+	) -> u128 {
+		let spent: u128 = self.orderbooks.values().map(|orderbook| orderbook.get_spend_by(account_id.to_string())).sum();
+		let staked: u128 = self.resolution_windows.iter()
+		.filter_map(|window| window.participants_to_outcome_to_stake.get(account_id))
+		.map(|stakes| stakes.values().sum::<u128>())
+		.sum();
+		return spent + staked;
+	}
+
+	pub fn clear_stakes_for(
+		&mut self,
+		account_id: &String
+	) {
+		for window in &mut self.resolution_windows {
+			if let Some(stakes) = window.participants_to_outcome_to_stake.get_mut(account_id) {
+				for (outcome_id, stake) in stakes.iter_mut() {
+					if let Some(total_staked) = window.staked_per_outcome.get_mut(outcome_id) {
+						*total_staked = total_staked.saturating_sub(*stake);
+					}
+					*stake = 0;
+				}
+			}
+		}
+	}
+
+	// Funds the market still holds for participants plus any bonds in escrow, a market can only be deleted once this is 0
+	pub fn get_held_total(
+		&self
+	) -> u128 {
+		let refunds: u128 = self.get_participants().iter().map(|account_id| self.get_refund_for(account_id)).sum();
+		let oracle_bond = if self.pending_oracle_request.is_some() { self.resolute_bond } else { 0 };
+		return refunds + self.creation_bond + oracle_bond;
+	}
+
 	pub fn cancel_dispute_participation(
 		&mut self,
 		round: u64,
-		outcome: Option<u64>
+		outcome: Option<Vec<u128>>
 	) -> u128{
-		let outcome_id = self.to_numerical_outcome(outcome);
+		let outcome_id = self.to_payout_id(&outcome);
 		let resolution_window = self.resolution_windows.get_mut(round as usize).expect("dispute round doesn't exist");
 		assert_ne!(outcome, resolution_window.outcome, "you cant cancel dispute stake for bonded outcome");
-		assert_ne!(outcome, self.winning_outcome, "you cant cancel dispute stake for winning outcome");
+		assert_ne!(outcome, self.payout_numerator, "you cant cancel dispute stake for winning outcome");
 		let mut to_return = 0;
 		resolution_window.participants_to_outcome_to_stake
 		.entry(env::predecessor_account_id())
@@ -422,8 +1024,8 @@ impl Market {
 		// need total staked per window
 		for window in &self.resolution_windows {
 			let empty_map = HashMap::new();
-			let winning_outcome_id = self.to_numerical_outcome(self.winning_outcome);
-			let window_outcome_id = self.to_numerical_outcome(window.outcome);
+			let winning_outcome_id = self.to_payout_id(&self.payout_numerator);
+			let window_outcome_id = self.to_payout_id(&window.outcome);
 			let round_participation = window.participants_to_outcome_to_stake
 			.get(&account_id)
 			.unwrap_or(&empty_map)
@@ -513,7 +1115,6 @@ impl Market {
         }
 	}
 
-	// TODO: Add get_liquidity function that doesn't need the spend argument
 	pub fn get_liquidity_available(
 		&self, 
 		outcome: u64, 
@@ -569,12 +1170,12 @@ impl Market {
 		&mut self,
 		account_id: String,
 	) {
-		let outcome_id = self.to_numerical_outcome(self.winning_outcome);
+		let outcome_id = self.to_payout_id(&self.payout_numerator);
 		for window in &mut self.resolution_windows {
 			window.participants_to_outcome_to_stake
 			.entry(account_id.to_string())
 			.or_insert(HashMap::new())
-			.entry(outcome_id)
+			.entry(outcome_id.to_string())
 			.and_modify(|staked| {
 				*staked = 0
 			})
@@ -584,6 +1185,13 @@ impl Market {
 
 }
 
+// Same as get_market_price_for, from the inverse books' best prices
+fn to_market_price(
+	best_prices: &BTreeMap<u64, u128>
+) -> u128 {
+	return 100 - best_prices.values().sum::<u128>();
+}
+
 impl Default for Market {
 	fn default() -> Self {
 		Self {
@@ -594,21 +1202,32 @@ impl Default for Market {
 			outcomes: 0,
 			outcome_tags: vec![],
 			categories: vec![],
+			metadata: MarketMetadata::default(),
 			last_price_for_outcomes: HashMap::new(),
+			trade_history: TradeHistory::default(),
 			creation_time: 0,
 			end_time: 0,
 			orderbooks: BTreeMap::new(),
-			winning_outcome: None,
-			resoluted: false,
+			payout_numerator: None,
+			status: MarketStatus::Open,
+			finalization_time: None,
 			resolute_bond: 0,
 			liquidity: 0,
-			disputed: false,
-			finalized: false,
 			fee_claimed: false,
 			fee_percentage: 0,
 			cost_percentage: 0,
 			api_source: "".to_string(),
-			resolution_windows: vec![]
+			resolution_windows: vec![],
+			oracle: None,
+			pending_oracle_request: None,
+			designated_reporter: "".to_string(),
+			designated_reporter_bond: 0,
+			window_config: WindowConfig::default(),
+			creation_bond: 0,
+			storage_by_account: HashMap::new(),
+			judge_votes: HashMap::new(),
+			order_limits: None,
+			circuit_breaker_from: 0,
 		}
 	}
 }
\ No newline at end of file
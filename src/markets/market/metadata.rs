@@ -0,0 +1,133 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+pub const MAX_TITLE_LENGTH: usize = 200;
+pub const MAX_TEXT_LENGTH: usize = 2000; // Resolution criteria, description and extra info
+pub const MAX_SOURCE_URLS: usize = 5;
+pub const MAX_URL_LENGTH: usize = 256;
+pub const MAX_OUTCOME_LABEL_LENGTH: usize = 64;
+pub const IMAGE_HASH_LENGTH: usize = 64; // Hex encoded sha256
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+// What the frontend renders for a market, title, image_hash and language are the only fields the creator can edit later
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq, Default)]
+pub struct MarketMetadata {
+	pub title: String,
+	pub resolution_criteria: String,
+	pub source_urls: Vec<String>,
+	pub outcome_labels: Vec<String>, // One per outcome, in outcome order
+	pub image_hash: Option<String>,
+	pub language: String, // ISO 639-1 code
+}
+
+impl MarketMetadata {
+	// Metadata for markets created without it, the title is the description cut to the title limit and binary markets don't have outcome tags so they're labeled NO and YES
+	pub fn from_legacy(
+		description: &String,
+		extra_info: &String,
+		outcome_tags: &Vec<String>
+	) -> Self {
+		let outcome_labels = if outcome_tags.len() == 0 {
+			vec!["NO".to_string(), "YES".to_string()]
+		} else {
+			outcome_tags.clone()
+		};
+
+		Self {
+			title: truncate(description, MAX_TITLE_LENGTH),
+			resolution_criteria: extra_info.to_string(),
+			source_urls: vec![],
+			outcome_labels,
+			image_hash: None,
+			language: DEFAULT_LANGUAGE.to_string(),
+		}
+	}
+
+	pub fn assert_valid(
+		&self,
+		outcomes: u64
+	) {
+		assert_valid_title(&self.title);
+		assert!(self.resolution_criteria.len() <= MAX_TEXT_LENGTH, "resolution criteria is too long");
+		assert!(self.source_urls.len() <= MAX_SOURCE_URLS, "too many source urls");
+		for url in &self.source_urls {
+			assert!(url.starts_with("https://") || url.starts_with("http://"), "source url should be an http(s) url");
+			assert!(url.len() <= MAX_URL_LENGTH, "source url is too long");
+		}
+
+		assert_eq!(self.outcome_labels.len() as u64, outcomes, "outcome labels should match the number of outcomes");
+		for (i, label) in self.outcome_labels.iter().enumerate() {
+			assert!(label.trim().len() > 0, "outcome label can't be empty");
+			assert!(label.len() <= MAX_OUTCOME_LABEL_LENGTH, "outcome label is too long");
+			assert!(!self.outcome_labels[..i].iter().any(|other| other.trim().to_lowercase() == label.trim().to_lowercase()), "duplicate outcome label");
+		}
+
+		if let Some(image_hash) = &self.image_hash {
+			assert_valid_image_hash(image_hash);
+		}
+		assert_valid_language(&self.language);
+	}
+
+	// Categorical markets already name their outcomes in outcome_tags so the labels have to be the same, binary markets have no tags and can label theirs freely.
+	// Labels aren't editable afterwards so this only has to hold at creation
+	pub fn assert_labels_match(
+		&self,
+		outcome_tags: &Vec<String>
+	) {
+		if outcome_tags.len() > 0 {
+			assert_eq!(&self.outcome_labels, outcome_tags, "outcome labels should match the outcome tags");
+		}
+	}
+
+	// Changes that don't affect how the market resolves, unset fields are kept
+	pub fn update(
+		&mut self,
+		title: Option<String>,
+		image_hash: Option<String>,
+		language: Option<String>
+	) {
+		if let Some(title) = title {
+			assert_valid_title(&title);
+			self.title = title;
+		}
+		if let Some(image_hash) = image_hash {
+			assert_valid_image_hash(&image_hash);
+			self.image_hash = Some(image_hash);
+		}
+		if let Some(language) = language {
+			assert_valid_language(&language);
+			self.language = language;
+		}
+	}
+}
+
+// Cut to at most max_length bytes without splitting a character
+fn truncate(
+	text: &String,
+	max_length: usize
+) -> String {
+	let mut end = std::cmp::min(text.len(), max_length);
+	while !text.is_char_boundary(end) {
+		end -= 1;
+	}
+	return text[..end].to_string();
+}
+
+fn assert_valid_title(
+	title: &String
+) {
+	assert!(title.trim().len() > 0, "title can't be empty");
+	assert!(title.len() <= MAX_TITLE_LENGTH, "title is too long");
+}
+
+fn assert_valid_image_hash(
+	image_hash: &String
+) {
+	assert!(image_hash.len() == IMAGE_HASH_LENGTH && image_hash.chars().all(|c| c.is_ascii_hexdigit()), "image hash should be 64 hex characters");
+}
+
+fn assert_valid_language(
+	language: &String
+) {
+	assert!(language.len() == 2 && language.chars().all(|c| c.is_ascii_lowercase()), "language should be a two letter ISO 639-1 code");
+}
@@ -0,0 +1,23 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+type SelfTradePrevention = super::self_trade::SelfTradePrevention;
+
+// What happens to the part of an order that isn't matched right away
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub enum OrderType {
+	Limit, // Rests in the orderbook at the order's price
+	ImmediateOrCancel, // Is refunded
+}
+
+impl Default for OrderType {
+	fn default() -> Self {
+		return OrderType::Limit;
+	}
+}
+
+// Per order settings, unset fields fall back to the protocol's self trade prevention default and a Limit order
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct OrderOptions {
+	pub self_trade_prevention: Option<SelfTradePrevention>,
+	pub order_type: Option<OrderType>,
+}
@@ -8,8 +8,16 @@ use std::convert::TryInto;
 pub mod order;
 pub type Order = order::Order;
 
+// Aggregated resting liquidity at a single price
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PriceLevel {
+	pub price: u128,
+	pub shares: u128,
+	pub orders: u64,
+}
+
 #[near_bindgen]
-#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug)]
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
 pub struct Orderbook {
 	pub root: Option<u128>,
 	pub best_price: Option<u128>,
@@ -97,10 +105,8 @@ impl Orderbook {
 		if current_best_price.is_none() {
 			self.best_price = Some(price);
 		} else {
-			if let Some((current_market_price, _ )) = self.open_orders.iter().next() {
-			    if price > *current_market_price {
-                    self.best_price = Some(price);
-                }
+			if price > current_best_price.unwrap() {
+				self.best_price = Some(price);
 			}
 		}
 	}
@@ -139,8 +145,8 @@ impl Orderbook {
         order_map.remove(&order_id);
         if order_map.is_empty() {
             self.orders_by_price.remove(&order.price);
-            if let Some((min_key, _ )) = self.orders_by_price.iter().next() {
-                self.best_price = Some(*min_key);
+            if let Some((max_key, _ )) = self.orders_by_price.iter().next_back() {
+                self.best_price = Some(*max_key);
             } else {
 				self.best_price = None;
 			}
@@ -148,6 +154,53 @@ impl Orderbook {
         return outstanding_spend;
 	}
 
+	// Looked up through orders_by_user so it doesn't scan every open order in the book
+	pub fn get_open_orders_of(
+		&self,
+		account_id: &String
+	) -> Vec<&Order> {
+		return match self.orders_by_user.get(account_id) {
+			Some(order_ids) => order_ids.iter().filter_map(|order_id| self.open_orders.get(order_id)).collect(),
+			None => vec![]
+		};
+	}
+
+	// Open orders of account_id in the price level fill_best_orders fills next
+	pub fn get_best_orders_of(
+		&self,
+		account_id: &String
+	) -> Vec<u128> {
+		return match self.orders_by_price.iter().next_back() {
+			Some((_, orders)) => orders.keys()
+			.filter(|order_id| self.open_orders.get(order_id).map_or(false, |order| &order.creator == account_id))
+			.cloned()
+			.collect(),
+			None => vec![]
+		};
+	}
+
+	// Takes unfilled shares off an open order without filling them, the order is removed once nothing's left. Returns the spend that's freed
+	pub fn decrement_order(
+		&mut self,
+		order_id: u128,
+		shares: u128
+	) -> u128 {
+		let order = self.open_orders.get_mut(&order_id).unwrap();
+		let shares_left = order.amt_of_shares - order.shares_filled;
+		if shares >= shares_left || order.spend - order.filled - shares * order.price < 100 {
+			return self.remove_order(order_id);
+		}
+
+		let freed = shares * order.price;
+		order.amt_of_shares -= shares;
+		order.spend -= freed;
+		let creator = order.creator.to_string();
+		let price = order.price;
+		*self.spend_by_user.get_mut(&creator).unwrap() -= freed;
+		*self.liquidity_by_price.get_mut(&price).unwrap() -= freed;
+		return freed;
+	}
+
 	// TODO: Should catch these rounding errors earlier, right now some "dust" will be lost.
 	pub fn fill_best_orders(
 		&mut self, 
@@ -155,7 +208,8 @@ impl Orderbook {
 	) {
 	    let mut to_remove : Vec<(u128, u128)> = vec![];
 
-		if let Some(( _ , current_order_map)) = self.orders_by_price.iter_mut().next() {
+		// Bids are filled from the highest price down, the same level best_price points at
+		if let Some(( _ , current_order_map)) = self.orders_by_price.iter_mut().next_back() {
 			// Iteratively fill market orders until done
             for (order_id, _) in current_order_map.iter_mut() {
 				let order = self.open_orders.get_mut(&order_id).unwrap();
@@ -210,12 +264,18 @@ impl Orderbook {
 		return claimable;
 	}
 
+	// Called on claim, open orders are taken off the book first so their unfilled spend can't be withdrawn again through cancel_order
 	pub fn delete_orders_for(
 		&mut self, 
 		account_id: String
 	) {
 		let empty_vec = &mut vec![];
 		let orders_by_user_copy = self.orders_by_user.get(&account_id).unwrap_or(empty_vec).clone();
+		for order_id in &orders_by_user_copy {
+			if self.open_orders.contains_key(order_id) {
+				self.remove_order(*order_id);
+			}
+		}
 		
 		self.spend_by_user
 		.entry(account_id.to_string())
@@ -271,6 +331,13 @@ impl Orderbook {
 		return claimable;
 	}
 
+	pub fn get_shares_filled_for(
+		&self,
+		account_id: String
+	) -> u128 {
+		return self.calc_claimable_amt(account_id) / 100;
+	}
+
 	pub fn get_spend_by(
 		&self, 
 		account_id: String
@@ -278,6 +345,22 @@ impl Orderbook {
 		return *self.spend_by_user.get(&account_id).unwrap_or(&0);
 	}
 
+	// Bids are matched from the highest price down so levels are returned highest first
+	pub fn get_price_levels(
+		&self,
+		levels: u64
+	) -> Vec<PriceLevel> {
+		return self.liquidity_by_price.iter().rev()
+		.map(|(price, _)| PriceLevel {
+			price: *price,
+			shares: self.get_liquidity_at_price(*price),
+			orders: self.orders_by_price.get(price).map(|orders| orders.len() as u64).unwrap_or(0),
+		})
+		.filter(|level| level.shares > 0)
+		.take(levels as usize)
+		.collect();
+	}
+
 	// TODO test if decrements on order fill
 	pub fn get_liquidity_at_price(
 		&self, 
@@ -1,7 +1,8 @@
 use std::string::String;
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
-use near_sdk::{near_bindgen, env};
+use near_sdk::near_bindgen;
+use crate::markets::time;
 
 #[near_bindgen]
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
@@ -28,7 +29,7 @@ impl Order {
 		filled: u128, 
 		shares_filled: u128
 	) -> Self {
-		let creation_time = env::block_timestamp() / 1000000;
+		let creation_time = time::now_ms();
 
 		Order {
 			id,
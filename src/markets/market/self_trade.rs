@@ -0,0 +1,17 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+// What happens when an incoming order would match resting orders of the same account, the protocol default is CancelNewest until the owner picks a mode
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub enum SelfTradePrevention {
+	Allow, // Orders match against the account's own resting orders like any other
+	CancelOldest, // The resting orders are cancelled and matching continues
+	CancelNewest, // What's left of the incoming order is cancelled instead of matched or placed
+	DecrementBoth, // Both sides are reduced by the overlapping shares without trading
+}
+
+impl Default for SelfTradePrevention {
+	fn default() -> Self {
+		return SelfTradePrevention::CancelNewest;
+	}
+}
@@ -0,0 +1,65 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+// Lifecycle of a market, Open, Closed and Reporting follow from the block time the rest is set through transitions
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+pub enum MarketStatus {
+	Pending, // Created while moderation is on, waiting for a moderator to approve it
+	Open, // Accepting orders
+	Closed, // Past end_time, in the grace period before reporting opens
+	Reporting, // Waiting for the initial resolution
+	Disputing(u64), // Resoluted, resolution window with this round is open for disputes
+	AwaitingJudge, // Dispute bond filled, the judge decides the outcome
+	Finalized(Option<Vec<u128>>), // Final payout numerator, None is invalid
+	Voided, // Market was called off, everyone is refunded
+}
+
+impl MarketStatus {
+	pub fn can_transition_to(
+		&self,
+		next: &MarketStatus
+	) -> bool {
+		return match (self, next) {
+			(MarketStatus::Pending, MarketStatus::Open) => true,
+			(MarketStatus::Open, MarketStatus::Closed) => true,
+			(MarketStatus::Closed, MarketStatus::Reporting) => true,
+			(MarketStatus::Reporting, MarketStatus::Disputing(round)) => *round == 1,
+			(MarketStatus::Disputing(round), MarketStatus::Disputing(next_round)) => *next_round == round + 1,
+			(MarketStatus::Disputing(_), MarketStatus::AwaitingJudge) => true,
+			(MarketStatus::Disputing(_), MarketStatus::Finalized(_)) => true,
+			(MarketStatus::AwaitingJudge, MarketStatus::Finalized(_)) => true,
+			(MarketStatus::Finalized(_), _) => false,
+			(MarketStatus::Voided, _) => false,
+			(_, MarketStatus::Voided) => true,
+			_ => false
+		};
+	}
+
+	// Time based statuses aren't stored, a stored Open can mean Open, Closed or Reporting
+	pub fn is_time_based(
+		&self
+	) -> bool {
+		return match self {
+			MarketStatus::Open | MarketStatus::Closed | MarketStatus::Reporting => true,
+			_ => false
+		};
+	}
+
+	pub fn is_resoluted(
+		&self
+	) -> bool {
+		return match self {
+			MarketStatus::Disputing(_) | MarketStatus::AwaitingJudge | MarketStatus::Finalized(_) => true,
+			_ => false
+		};
+	}
+
+	pub fn is_finalized(
+		&self
+	) -> bool {
+		return match self {
+			MarketStatus::Finalized(_) => true,
+			_ => false
+		};
+	}
+}
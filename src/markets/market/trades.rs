@@ -0,0 +1,104 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+// Oldest trades are overwritten once a market has recorded this many
+pub const MAX_TRADES: u64 = 1000;
+
+// A single fill, time in ms
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+pub struct Trade {
+	pub time: u64,
+	pub outcome: u64,
+	pub price: u128,
+	pub shares: u128,
+}
+
+// OHLC of an outcome over [start_time, start_time + interval), volume in shares
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Candle {
+	pub start_time: u64,
+	pub open: u128,
+	pub high: u128,
+	pub low: u128,
+	pub close: u128,
+	pub volume: u128,
+}
+
+// Ring buffer of the last MAX_TRADES trades, trades are numbered in order of recording starting at 0
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, Default)]
+pub struct TradeHistory {
+	pub trades: Vec<Trade>,
+	pub total: u64, // Number of trades ever recorded
+}
+
+impl TradeHistory {
+	pub fn record(
+		&mut self,
+		trade: Trade
+	) {
+		if (self.trades.len() as u64) < MAX_TRADES {
+			self.trades.push(trade);
+		} else {
+			let index = (self.total % MAX_TRADES) as usize;
+			self.trades[index] = trade;
+		}
+		self.total += 1;
+	}
+
+	// Number of the oldest trade that's still retained
+	pub fn first_retained(
+		&self
+	) -> u64 {
+		return self.total - self.trades.len() as u64;
+	}
+
+	// Trades numbered from onwards, oldest first, trades that were already overwritten are skipped
+	pub fn get_trades(
+		&self,
+		from: u64,
+		limit: u64
+	) -> Vec<&Trade> {
+		let start = std::cmp::max(from, self.first_retained());
+		let end = std::cmp::min(start.saturating_add(limit), self.total);
+		return (start..end).map(|number| &self.trades[(number % MAX_TRADES) as usize]).collect();
+	}
+
+	// Trades numbered from onwards, newest first, without copying them
+	pub fn iter_newest_first(
+		&self,
+		from: u64
+	) -> impl Iterator<Item = &Trade> {
+		let start = std::cmp::max(from, self.first_retained());
+		return (start..self.total).rev().map(move |number| &self.trades[(number % MAX_TRADES) as usize]);
+	}
+
+	pub fn get_candles(
+		&self,
+		outcome: u64,
+		interval: u64
+	) -> Vec<Candle> {
+		let mut candles: Vec<Candle> = vec![];
+		for trade in self.get_trades(0, MAX_TRADES) {
+			if trade.outcome != outcome { continue }
+			let start_time = trade.time - trade.time % interval;
+
+			match candles.last_mut() {
+				Some(candle) if candle.start_time == start_time => {
+					candle.high = std::cmp::max(candle.high, trade.price);
+					candle.low = std::cmp::min(candle.low, trade.price);
+					candle.close = trade.price;
+					candle.volume += trade.shares;
+				},
+				_ => candles.push(Candle {
+					start_time,
+					open: trade.price,
+					high: trade.price,
+					low: trade.price,
+					close: trade.price,
+					volume: trade.shares,
+				})
+			}
+		}
+		return candles;
+	}
+}
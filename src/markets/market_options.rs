@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+type OracleConfig = super::oracle::OracleConfig;
+type WindowConfig = super::time::WindowConfig;
+type MarketMetadata = super::market::metadata::MarketMetadata;
+
+// Optional market settings, unset fields fall back to reporting by the creator through the judge, the protocol's window config and metadata derived from the description
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MarketOptions {
+	pub oracle: Option<OracleConfig>,
+	pub designated_reporter: Option<String>,
+	pub window_config: Option<WindowConfig>,
+	pub metadata: Option<MarketMetadata>,
+}
@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, HashMap};
+use borsh::{BorshDeserialize, BorshSerialize};
+use super::Markets;
+use super::market::{Market, MarketStatus, ResolutionWindow};
+use super::market::orderbook::Orderbook;
+use super::market::metadata::MarketMetadata;
+
+// Stored in front of the state from v2 onwards, v1 has no version tag and is recognized by its layout.
+// Bump this and add the previous layout below whenever a stored field changes once v2 is deployed.
+// v2 is a single step from v1, nothing in between was deployed so it's the whole current layout: payout numerators and market statuses,
+// window configs and designated reporters, indexes, archived markets, the treasury, storage accounts, operators, pause flags
+// and the circuit breaker, roles and the judge threshold, moderation, self trade prevention, order limits and market metadata
+pub const STATE_VERSION: u32 = 2;
+
+// Layout of the contract as deployed before payout numerators and market statuses
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct MarketsV1 {
+	pub creator: String,
+	pub active_markets: BTreeMap<u64, MarketV1>,
+	pub nonce: u64,
+	pub fdai_balances: HashMap<String, u128>,
+	pub fdai_circulation: u128,
+	pub fdai_in_protocol: u128,
+	pub fdai_outside_escrow: u128,
+	pub user_count: u64,
+	pub max_fee_percentage: u128,
+	pub creation_bond: u128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct MarketV1 {
+	pub id: u64,
+	pub description: String,
+	pub extra_info: String,
+	pub creator: String,
+	pub outcomes: u64,
+	pub outcome_tags: Vec<String>,
+	pub categories: Vec<String>,
+	pub last_price_for_outcomes: HashMap<u64, u128>,
+	pub creation_time: u64,
+	pub end_time: u64,
+	pub orderbooks: BTreeMap<u64, Orderbook>,
+	pub winning_outcome: Option<u64>, // None is invalid
+	pub resoluted: bool,
+	pub resolute_bond: u128,
+	pub liquidity: u128,
+	pub disputed: bool,
+	pub finalized: bool,
+	pub fee_claimed: bool,
+	pub fee_percentage: u128,
+	pub cost_percentage: u128,
+	pub api_source: String,
+	pub resolution_windows: Vec<ResolutionWindowV1>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct ResolutionWindowV1 {
+	pub round: u64,
+	pub participants_to_outcome_to_stake: HashMap<String, HashMap<u64, u128>>, // Outcome ids equal to outcomes are invalid
+	pub required_bond_size: u128,
+	pub staked_per_outcome: HashMap<u64, u128>,
+	pub end_time: u64,
+	pub outcome: Option<u64>,
+}
+
+// Reads whatever layout is stored and converts it to the current one
+pub fn migrate_state(
+	state: &[u8]
+) -> Markets {
+	if let Ok(markets) = MarketsV1::try_from_slice(state) {
+		return markets.migrate();
+	}
+
+	assert!(state.len() >= 4, "unknown state layout");
+	let version = u32::try_from_slice(&state[0..4]).unwrap();
+	assert_ne!(version, STATE_VERSION, "state is already up to date");
+	panic!("unknown state version {}", version);
+}
+
+// v1 outcome ids to payout numerators, the outcome id equal to outcomes was used for invalid
+fn to_payout(
+	outcome: u64,
+	outcomes: u64
+) -> Option<Vec<u128>> {
+	if outcome >= outcomes { return None }
+	return Some((0..outcomes).map(|i| if i == outcome { 100 } else { 0 }).collect());
+}
+
+impl MarketsV1 {
+	pub fn migrate(
+		self
+	) -> Markets {
+		let mut markets = Markets {
+			roles: super::roles::initial_roles(self.creator.to_string()),
+			creator: self.creator,
+			nonce: self.nonce,
+			fdai_balances: self.fdai_balances,
+			fdai_circulation: self.fdai_circulation,
+			fdai_in_protocol: self.fdai_in_protocol,
+			fdai_outside_escrow: self.fdai_outside_escrow,
+			user_count: self.user_count,
+			max_fee_percentage: self.max_fee_percentage,
+			creation_bond: self.creation_bond,
+			..Markets::default()
+		};
+
+		for (id, market) in self.active_markets {
+			let market = market.migrate();
+			for category in &market.categories {
+				markets.markets_by_category.entry(category.to_string()).or_insert(Vec::new()).push(id);
+			}
+			markets.markets_by_creator.entry(market.creator.to_string()).or_insert(Vec::new()).push(id);
+			for account_id in market.get_participants() {
+				markets.markets_by_account.entry(account_id).or_insert(Vec::new()).push(id);
+			}
+			markets.active_markets.insert(id, market);
+		}
+
+		return markets;
+	}
+}
+
+impl MarketV1 {
+	pub fn migrate(
+		self
+	) -> Market {
+		let outcomes = self.outcomes;
+		let payout_numerator = match self.winning_outcome {
+			Some(outcome) if self.resoluted => to_payout(outcome, outcomes),
+			_ => None
+		};
+		let last_round = self.resolution_windows.last().map(|window| window.round).unwrap_or(0);
+		let last_window_end_time = self.resolution_windows.last().map(|window| window.end_time);
+
+		let status = if self.finalized {
+			MarketStatus::Finalized(payout_numerator.clone())
+		} else if self.disputed {
+			MarketStatus::AwaitingJudge
+		} else if self.resoluted {
+			MarketStatus::Disputing(last_round)
+		} else {
+			MarketStatus::Open
+		};
+
+		let mut market = Market {
+			metadata: MarketMetadata::from_legacy(&self.description, &self.extra_info, &self.outcome_tags),
+			id: self.id,
+			description: self.description,
+			extra_info: self.extra_info,
+			designated_reporter: self.creator.to_string(),
+			creator: self.creator,
+			outcomes,
+			outcome_tags: self.outcome_tags,
+			categories: self.categories,
+			last_price_for_outcomes: self.last_price_for_outcomes,
+			creation_time: self.creation_time,
+			end_time: self.end_time,
+			orderbooks: self.orderbooks,
+			payout_numerator,
+			status,
+			finalization_time: if self.finalized { last_window_end_time } else { None },
+			resolute_bond: self.resolute_bond,
+			liquidity: self.liquidity,
+			fee_claimed: self.fee_claimed,
+			fee_percentage: self.fee_percentage,
+			cost_percentage: self.cost_percentage,
+			api_source: self.api_source,
+			..Market::default()
+		};
+
+		let resolution_windows = self.resolution_windows.into_iter().map(|window| {
+			let to_payout_id = |outcome: &u64| market.to_payout_id(&to_payout(*outcome, outcomes));
+			ResolutionWindow {
+				round: window.round,
+				participants_to_outcome_to_stake: window.participants_to_outcome_to_stake.iter().map(|(account_id, stakes)| {
+					(account_id.to_string(), stakes.iter().map(|(outcome, stake)| (to_payout_id(outcome), *stake)).collect())
+				}).collect(),
+				required_bond_size: window.required_bond_size,
+				staked_per_outcome: window.staked_per_outcome.iter().map(|(outcome, stake)| (to_payout_id(outcome), *stake)).collect(),
+				end_time: window.end_time,
+				outcome: window.outcome.and_then(|outcome| to_payout(outcome, outcomes)),
+			}
+		}).collect();
+		market.resolution_windows = resolution_windows;
+
+		return market;
+	}
+}
@@ -0,0 +1,47 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+// What an account allows an operator to do on its behalf
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+pub struct OperatorApproval {
+	pub market_id: Option<u64>, // Only this market if set, every market otherwise
+	pub spend_limit: Option<u128>, // Max total spend on orders placed by the operator minus what was refunded, unlimited if None
+	pub spent: u128,
+}
+
+impl OperatorApproval {
+	pub fn new(
+		market_id: Option<u64>,
+		spend_limit: Option<u128>
+	) -> Self {
+		Self {
+			market_id,
+			spend_limit,
+			spent: 0,
+		}
+	}
+
+	// Checks if the operator may spend this much more in market_id, the spend is recorded if it can
+	pub fn use_approval(
+		&mut self,
+		market_id: u64,
+		spend: u128
+	) -> Result<(), String> {
+		if let Some(approved_market_id) = self.market_id {
+			if approved_market_id != market_id { return Err("operator isn't approved for this market".to_string()) }
+		}
+		if let Some(spend_limit) = self.spend_limit {
+			if self.spent + spend > spend_limit { return Err("operator spend limit exceeded".to_string()) }
+		}
+		self.spent += spend;
+		return Ok(());
+	}
+
+	// Spend that was refunded to the account, e.g. unfilled or cancelled, can be used again
+	pub fn credit(
+		&mut self,
+		amount: u128
+	) {
+		self.spent = self.spent.saturating_sub(amount);
+	}
+}
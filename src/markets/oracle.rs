@@ -0,0 +1,28 @@
+use std::string::String;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use near_sdk::ext_contract;
+
+// Oracle contract and the request a market resolutes by
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct OracleConfig {
+	pub contract_id: String,
+	pub request_id: String,
+}
+
+// What an oracle reports back, a payout_numerator of None resolutes the market as invalid
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OracleReport {
+	pub payout_numerator: Option<Vec<u128>>,
+}
+
+// Interface oracle contracts have to implement, returns None if the request hasn't been answered yet
+#[ext_contract(ext_oracle)]
+pub trait Oracle {
+	fn get_outcome(&self, request_id: String) -> Option<OracleReport>;
+}
+
+#[ext_contract(ext_self)]
+pub trait OracleCallback {
+	fn on_oracle_outcome(&mut self, market_id: u64, requester: String);
+}
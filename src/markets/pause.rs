@@ -0,0 +1,65 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+// Actions that can be paused separately, cancels and withdrawals can't be paused
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+	Trading, // Placing orders
+	Reporting, // Resoluting, disputing and finalizing
+	Claiming, // Claiming earnings and creator fees
+}
+
+impl Action {
+	pub fn name(
+		&self
+	) -> &'static str {
+		return match self {
+			Action::Trading => "trading",
+			Action::Reporting => "reporting",
+			Action::Claiming => "claiming",
+		};
+	}
+}
+
+// all pauses every action
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, Default, PartialEq)]
+pub struct PauseFlags {
+	pub all: bool,
+	pub trading: bool,
+	pub reporting: bool,
+	pub claiming: bool,
+}
+
+impl PauseFlags {
+	pub fn is_paused(
+		&self,
+		action: Action
+	) -> bool {
+		return self.all || match action {
+			Action::Trading => self.trading,
+			Action::Reporting => self.reporting,
+			Action::Claiming => self.claiming,
+		};
+	}
+
+	// No action sets the all flag
+	pub fn set(
+		&mut self,
+		action: Option<Action>,
+		paused: bool
+	) {
+		match action {
+			None => self.all = paused,
+			Some(Action::Trading) => self.trading = paused,
+			Some(Action::Reporting) => self.reporting = paused,
+			Some(Action::Claiming) => self.claiming = paused,
+		}
+	}
+}
+
+// Pauses trading on a market once any outcome's price moves more than max_price_move within window (ms), disabled if max_price_move is 0
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, Default, PartialEq)]
+pub struct CircuitBreaker {
+	pub max_price_move: u128,
+	pub window: u64,
+}
@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use super::market::Market;
+
+// An account's position in a single market, amounts are in fdai and shares are valued at the current market prices
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PortfolioEntry {
+	pub market_id: u64,
+	pub open_order_value: u128,
+	pub shares_by_outcome: BTreeMap<u64, u128>,
+	pub stake_by_round: Vec<u128>,
+	pub claimable: u128, // 0 until the market is finalized or voided
+	pub unrealized_pnl: i128,
+}
+
+impl PortfolioEntry {
+	pub fn from_market(
+		market: &Market,
+		account_id: &String
+	) -> Self {
+		let market_prices = market.get_market_prices_for();
+		let mut open_order_value = 0;
+		let mut shares_by_outcome = BTreeMap::new();
+		let mut position_value = 0;
+		let mut spent = 0;
+
+		for (outcome, orderbook) in market.orderbooks.iter() {
+			let shares = orderbook.get_shares_filled_for(account_id.to_string());
+			open_order_value += orderbook.get_open_order_value_for(account_id.to_string());
+			position_value += shares * market_prices.get(outcome).unwrap();
+			spent += orderbook.get_spend_by(account_id.to_string());
+			shares_by_outcome.insert(*outcome, shares);
+		}
+
+		let claimable = if market.is_voided() {
+			market.get_refund_for(account_id)
+		} else if market.is_finalized() {
+			market.get_claimable_for(account_id.to_string())
+		} else {
+			0
+		};
+
+		Self {
+			market_id: market.id,
+			open_order_value,
+			shares_by_outcome,
+			stake_by_round: market.get_stake_by_round_for(account_id),
+			claimable,
+			unrealized_pnl: (position_value + open_order_value) as i128 - spent as i128,
+		}
+	}
+}
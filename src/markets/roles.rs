@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+	Owner, // Grants and revokes roles and updates protocol settings
+	Judge, // Votes on the outcome of markets that are awaiting the judge
+	Minter, // Mints fdai
+	MarketModerator, // Deletes markets
+	Pauser, // Pauses and unpauses actions
+	FeeCollector, // Decides where protocol fees and swept funds go
+}
+
+pub fn all_roles() -> Vec<Role> {
+	return vec![Role::Owner, Role::Judge, Role::Minter, Role::MarketModerator, Role::Pauser, Role::FeeCollector];
+}
+
+// The deploying account starts out with every role
+pub fn initial_roles(
+	account_id: String
+) -> HashMap<String, Vec<Role>> {
+	let mut roles = HashMap::new();
+	roles.insert(account_id, all_roles());
+	return roles;
+}
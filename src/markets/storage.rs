@@ -0,0 +1,37 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+pub const STORAGE_PRICE_PER_BYTE: u128 = 10000000000000000000; // 1e19 yoctoNEAR
+
+// Upper bounds of what each kind of entry adds to the serialized state
+pub const BALANCE_ENTRY_BYTES: u64 = 100; // fdai_balances
+pub const ORDER_ENTRY_BYTES: u64 = 300; // open_orders, orders_by_price, liquidity_by_price, orders_by_user and spend_by_user
+pub const STAKE_ENTRY_BYTES: u64 = 150; // participants_to_outcome_to_stake and staked_per_outcome
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Default)]
+pub struct StorageAccount {
+	pub deposit: u128, // In yoctoNEAR
+	pub bytes_used: u64,
+}
+
+impl StorageAccount {
+	pub fn locked(
+		&self
+	) -> u128 {
+		return self.bytes_used as u128 * STORAGE_PRICE_PER_BYTE;
+	}
+}
+
+// NEP-145 storage balance
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct StorageBalance {
+	pub total: u128,
+	pub available: u128,
+}
+
+// NEP-145 storage balance bounds, there's no max since accounts keep adding orders
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StorageBalanceBounds {
+	pub min: u128,
+	pub max: Option<u128>,
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+fn finalization_timestamp_ns() -> u64 {
+	return market_end_timestamp_ns() + 1800000000000;
+}
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	contract.set_claim_period(time::HOUR);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), vec!["sports".to_string()], market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.place_order(0, 0, 5000, 50);
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 1, 5000, 50);
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	testing_env!(get_context(carol(), finalization_timestamp_ns()));
+	contract.finalize_market(0, None);
+	return contract;
+}
+
+#[test]
+fn test_archive_after_all_claims() {
+	let mut contract = init_tests();
+	contract.claim_earnings(0, carol());
+	testing_env!(get_context(alice(), finalization_timestamp_ns()));
+	contract.claim_earnings(0, alice());
+	contract.archive_market(0);
+
+	let archived = contract.get_archived_market(0);
+	assert_eq!(archived.payout_numerator, Some(vec![100, 0]));
+	assert_eq!(archived.swept_to_treasury, 0);
+	assert_eq!(contract.get_all_markets().len(), 0);
+	assert_eq!(contract.get_markets(0, 10, None).len(), 0);
+	assert_eq!(contract.get_archived_markets(0, 10).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "market still has unclaimed funds")]
+fn test_archive_with_unclaimed_funds() {
+	let mut contract = init_tests();
+	testing_env!(get_context(alice(), finalization_timestamp_ns()));
+	contract.claim_earnings(0, alice());
+	contract.archive_market(0);
+}
+
+#[test]
+#[should_panic(expected = "market isn't finalized yet")]
+fn test_archive_unfinalized_market() {
+	let mut contract = init_tests();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms() + 1000, 0, 0, "test".to_string());
+	contract.archive_market(1);
+}
+
+#[test]
+fn test_sweep_after_claim_period() {
+	let mut contract = init_tests();
+	let unclaimed = contract.get_claimable(0, carol());
+	assert_eq!(unclaimed, 10000 + to_dai(5));
+
+	testing_env!(get_context(bob(), finalization_timestamp_ns() + time::ms_to_ns(time::HOUR)));
+	contract.archive_market(0);
+
+	assert_eq!(contract.get_archived_market(0).swept_to_treasury, unclaimed);
+	assert_eq!(contract.get_fdai_balance(judge()), unclaimed);
+}
+
+#[test]
+fn test_archive_removes_account_and_pause_entries() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), finalization_timestamp_ns()));
+	contract.pause(Some(0), Some(Action::Claiming));
+	assert!(contract.market_pauses.contains_key(&0));
+	assert_eq!(contract.markets_by_account.get(&carol()), Some(&vec![0]));
+
+	testing_env!(get_context(bob(), finalization_timestamp_ns() + time::ms_to_ns(time::HOUR)));
+	contract.archive_market(0);
+
+	assert!(!contract.market_pauses.contains_key(&0));
+	assert_eq!(contract.markets_by_account.get(&carol()), None);
+	assert_eq!(contract.markets_by_account.get(&alice()), None);
+	assert_eq!(contract.get_portfolio(carol()), vec![]);
+}
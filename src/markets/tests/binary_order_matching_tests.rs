@@ -3,6 +3,8 @@ use super::*;
 fn simplest_binary_order_matching_test() {
 	testing_env!(get_context(carol(), current_block_timestamp()));
 	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
 
@@ -22,6 +24,8 @@ fn simplest_binary_order_matching_test() {
 fn partial_binary_order_matching_test() {
 	testing_env!(get_context(carol(), current_block_timestamp()));
 	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
 
@@ -41,3 +45,108 @@ fn partial_binary_order_matching_test() {
 	assert_eq!(filled_no_orders.len(), 1);
 	assert_eq!(filled_yes_orders.len(), 2);
 }
+
+fn init_self_trade_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.place_order(0, 0, 5000, 50);
+	return contract;
+}
+
+#[test]
+fn self_trade_cancel_oldest_test() {
+	let mut contract = init_self_trade_tests();
+	contract.place_order_with_options(0, 1, 5000, 50, OrderOptions { self_trade_prevention: Some(SelfTradePrevention::CancelOldest), order_type: None });
+
+	assert_eq!(contract.get_open_orders(0, 0).len(), 0);
+	assert_eq!(contract.get_open_orders(0, 1).len(), 1);
+	assert_eq!(contract.get_filled_orders(0, 1).len(), 0);
+	assert_eq!(contract.get_market(0).liquidity, 0);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - 5000);
+}
+
+#[test]
+fn self_trade_cancel_newest_test() {
+	let mut contract = init_self_trade_tests();
+	let storage_available = contract.storage_balance_of(carol()).unwrap().available;
+	contract.place_order_with_options(0, 1, 5000, 50, OrderOptions { self_trade_prevention: Some(SelfTradePrevention::CancelNewest), order_type: None });
+
+	assert_eq!(contract.get_open_orders(0, 0).len(), 1);
+	assert_eq!(contract.get_open_orders(0, 1).len(), 0);
+	assert_eq!(contract.get_filled_orders(0, 1).len(), 0);
+	assert_eq!(contract.get_market(0).liquidity, 0);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - 5000);
+	assert_eq!(contract.storage_balance_of(carol()).unwrap().available, storage_available);
+}
+
+#[test]
+fn self_trade_decrement_both_test() {
+	let mut contract = init_self_trade_tests();
+	contract.place_order_with_options(0, 1, 2000, 50, OrderOptions { self_trade_prevention: Some(SelfTradePrevention::DecrementBoth), order_type: None });
+
+	let open_no_orders = contract.get_open_orders(0, 0);
+	assert_eq!(open_no_orders.len(), 1);
+	assert_eq!(open_no_orders.get(&0).unwrap().amt_of_shares, 60);
+	assert_eq!(open_no_orders.get(&0).unwrap().spend, 3000);
+	assert_eq!(contract.get_open_orders(0, 1).len(), 0);
+	assert_eq!(contract.get_market(0).liquidity, 0);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - 3000);
+	assert_eq!(contract.get_orderbook(0, 0, 1)[0].shares, 60);
+}
+
+#[test]
+fn self_trade_cancel_oldest_keeps_matching_others_test() {
+	let mut contract = init_self_trade_tests();
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 0, 2000, 50);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order_with_options(0, 1, 5000, 50, OrderOptions { self_trade_prevention: Some(SelfTradePrevention::CancelOldest), order_type: None });
+
+	assert_eq!(contract.get_open_orders(0, 0).len(), 0);
+	assert_eq!(contract.get_filled_orders(0, 0).len(), 1);
+	assert_eq!(contract.get_open_orders(0, 1).len(), 1);
+	assert_eq!(contract.get_market(0).liquidity, 4000);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - 5000);
+}
+
+#[test]
+fn self_trade_protocol_default_test() {
+	let mut contract = init_self_trade_tests();
+	assert_eq!(contract.get_self_trade_prevention(), SelfTradePrevention::CancelNewest);
+
+	contract.place_order(0, 1, 5000, 50);
+	assert_eq!(contract.get_open_orders(0, 0).len(), 1);
+	assert_eq!(contract.get_open_orders(0, 1).len(), 0);
+	assert_eq!(contract.get_market(0).liquidity, 0);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - 5000);
+
+	contract.place_order_with_options(0, 1, 5000, 50, OrderOptions { self_trade_prevention: Some(SelfTradePrevention::Allow), order_type: None });
+	assert_eq!(contract.get_open_orders(0, 0).len(), 0);
+	assert_eq!(contract.get_market(0).liquidity, 10000);
+}
+
+#[test]
+fn self_trade_owner_default_test() {
+	let mut contract = init_self_trade_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_self_trade_prevention(SelfTradePrevention::Allow);
+	assert_eq!(contract.get_self_trade_prevention(), SelfTradePrevention::Allow);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(0, 1, 5000, 50);
+	assert_eq!(contract.get_open_orders(0, 0).len(), 0);
+	assert_eq!(contract.get_market(0).liquidity, 10000);
+}
+
+#[test]
+#[should_panic(expected = "only the owner can update the self trade prevention default")]
+fn self_trade_protocol_default_without_role_test() {
+	let mut contract = init_self_trade_tests();
+	contract.set_self_trade_prevention(SelfTradePrevention::CancelNewest);
+}
@@ -4,6 +4,7 @@ use super::*;
 fn test_bst_additions() {
 	testing_env!(get_context(carol(), current_block_timestamp()));	
 	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), 100010101001010);
 	
@@ -75,6 +76,7 @@ fn test_bst_additions() {
 fn test_bst_removal() {
 	testing_env!(get_context(carol(), current_block_timestamp()));	
 	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_creation_timestamp());
 	contract.place_order(0, 0, 100000, 50);
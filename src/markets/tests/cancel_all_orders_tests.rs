@@ -0,0 +1,103 @@
+use super::*;
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 0, 3000, 40);
+	contract.place_order(0, 1, 1000, 20);
+	contract.place_order(1, 2, 2000, 10);
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 0, 1000, 30);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	return contract;
+}
+
+fn open_orders(contract: &Markets, market_id: u64, outcome: u64) -> usize {
+	return contract.get_market(market_id).orderbooks.get(&outcome).unwrap().open_orders.len();
+}
+
+#[test]
+fn test_cancel_all_orders_in_market() {
+	let mut contract = init_tests();
+	let returned = contract.cancel_all_orders(Some(0), None);
+	assert_eq!(returned, 9000);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - 2000);
+	assert_eq!(open_orders(&contract, 0, 0), 1);
+	assert_eq!(open_orders(&contract, 0, 1), 0);
+	assert_eq!(open_orders(&contract, 1, 2), 1);
+	assert_eq!(contract.get_market_price(0, 1), 70);
+}
+
+#[test]
+fn test_cancel_all_orders_for_outcome() {
+	let mut contract = init_tests();
+	let returned = contract.cancel_all_orders(Some(0), Some(1));
+	assert_eq!(returned, 1000);
+	assert_eq!(open_orders(&contract, 0, 0), 3);
+	assert_eq!(open_orders(&contract, 0, 1), 0);
+}
+
+#[test]
+fn test_cancel_all_orders_globally() {
+	let mut contract = init_tests();
+	let returned = contract.cancel_all_orders(None, None);
+	assert_eq!(returned, 11000);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100));
+	assert_eq!(open_orders(&contract, 1, 2), 0);
+	assert_eq!(contract.get_fdai_balance(alice()), to_dai(100) - 1000);
+
+	let balance = contract.storage_balance_of(carol()).unwrap();
+	assert_eq!(balance.available, storage_deposit_amount() - storage::BALANCE_ENTRY_BYTES as u128 * storage::STORAGE_PRICE_PER_BYTE);
+}
+
+#[test]
+fn test_cancel_all_orders_partially_filled() {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.place_order(0, 0, 5000, 50);
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 1, 3000, 50);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let returned = contract.cancel_all_orders(Some(0), None);
+	assert_eq!(returned, 2000);
+	assert_eq!(contract.get_market(0).orderbooks.get(&0).unwrap().get_shares_filled_for(carol()), 60);
+}
+
+#[test]
+fn test_cancel_all_orders_without_orders() {
+	let mut contract = init_tests();
+	testing_env!(get_context(bob(), current_block_timestamp()));
+	assert_eq!(contract.cancel_all_orders(None, None), 0);
+	assert_eq!(contract.cancel_all_orders(Some(0), None), 0);
+}
+
+#[test]
+#[should_panic(expected = "not authorized to act for this account")]
+fn test_cancel_all_orders_for_other_account() {
+	let mut contract = init_tests();
+	testing_env!(get_context(bob(), current_block_timestamp()));
+	contract.cancel_all_orders_for(carol(), Some(0), None);
+}
+
+#[test]
+#[should_panic(expected = "outcome doesn't exist")]
+fn test_cancel_all_orders_invalid_outcome() {
+	let mut contract = init_tests();
+	contract.cancel_all_orders(Some(0), Some(2));
+}
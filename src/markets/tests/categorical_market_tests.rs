@@ -3,6 +3,7 @@ use super::*;
 fn test_categorical_market_automated_matcher() {
 	testing_env!(get_context(carol(), current_block_timestamp()));
 	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(),  market_end_timestamp_ms(), 0, 0, "test".to_string());
 
@@ -16,6 +17,7 @@ fn test_categorical_market_automated_matcher() {
 
 	testing_env!(get_context(alice(), current_block_timestamp()));
 
+	register_storage(&mut contract, alice(), current_block_timestamp());
 	contract.claim_fdai();
 
 	// alice fills all orders
@@ -50,3 +52,29 @@ fn test_categorical_market_automated_matcher() {
 	assert_eq!(filled_1_orders.len(), 2);
 	assert_eq!(filled_2_orders.len(), 1);
 }
+
+#[test]
+fn test_categorical_self_trade_decrement_both() {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+
+	contract.place_order(0, 1, 3000, 30);
+	contract.place_order(0, 2, 1500, 30);
+
+	// Only the 50 shares carol has in both books overlap with the incoming order
+	contract.place_order_with_options(0, 0, 4000, 40, OrderOptions { self_trade_prevention: Some(SelfTradePrevention::DecrementBoth), order_type: None });
+
+	let open_1_orders = contract.get_open_orders(0, 1);
+	assert_eq!(open_1_orders.get(&0).unwrap().amt_of_shares, 50);
+	assert_eq!(open_1_orders.get(&0).unwrap().spend, 1500);
+	assert_eq!(contract.get_open_orders(0, 2).len(), 0);
+
+	let open_0_orders = contract.get_open_orders(0, 0);
+	assert_eq!(open_0_orders.get(&0).unwrap().amt_of_shares, 50);
+	assert_eq!(open_0_orders.get(&0).unwrap().spend, 2000);
+	assert_eq!(contract.get_market(0).liquidity, 0);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - 3500);
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+fn finalization_timestamp_ns() -> u64 {
+	return market_end_timestamp_ns() + 1800000000000;
+}
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	for market_id in 0..3 {
+		contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+		contract.place_order(market_id, 0, 5000, 50);
+		contract.place_order(market_id, 1, 5000, 50);
+	}
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	contract.resolute_market(1, None, to_dai(5));
+
+	testing_env!(get_context(carol(), finalization_timestamp_ns()));
+	contract.finalize_market(0, None);
+	contract.finalize_market(1, None);
+	return contract;
+}
+
+#[test]
+fn test_claim_all() {
+	let mut contract = init_tests();
+	let claimable = contract.get_claimable(0, carol()) + contract.get_claimable(1, carol());
+	let initial_balance = contract.get_fdai_balance(carol());
+
+	let results = contract.claim_all(carol(), vec![0, 1]);
+	assert_eq!(results.iter().map(|result| result.claimed).sum::<u128>(), claimable);
+	assert!(results.iter().all(|result| result.error.is_none()));
+	assert_eq!(contract.get_fdai_balance(carol()), initial_balance + claimable);
+	assert_eq!(contract.get_claimable(0, carol()), 0);
+	assert_eq!(contract.get_claimable(1, carol()), 0);
+}
+
+#[test]
+fn test_claim_all_skips_unready_markets() {
+	let mut contract = init_tests();
+	let claimable = contract.get_claimable(0, carol());
+
+	let results = contract.claim_all(carol(), vec![0, 2, 7]);
+	assert_eq!(results, vec![
+		ClaimResult { market_id: 0, claimed: claimable, error: None },
+		ClaimResult { market_id: 2, claimed: 0, error: Some("market isn't finalized yet".to_string()) },
+		ClaimResult { market_id: 7, claimed: 0, error: Some("market doesn't exist".to_string()) },
+	]);
+	assert!(contract.get_claimable(2, carol()) > 0);
+}
+
+#[test]
+fn test_claim_all_twice() {
+	let mut contract = init_tests();
+	contract.claim_all(carol(), vec![0]);
+	let results = contract.claim_all(carol(), vec![0]);
+	assert_eq!(results[0].claimed, 0);
+}
+
+#[test]
+#[should_panic(expected = "market isn't finalized yet")]
+fn test_claim_earnings_unfinalized_market() {
+	let mut contract = init_tests();
+	contract.claim_earnings(2, carol());
+}
@@ -4,6 +4,8 @@ use super::*;
 fn test_payout() {
 	testing_env!(get_context(carol(), current_block_timestamp()));
 	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 4, outcome_tags(4), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
 
@@ -11,6 +13,7 @@ fn test_payout() {
 	contract.place_order(0, 3, 1000, 10);
 
 	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.place_order(0, 1, 1000, 10);
 	contract.place_order(0, 2, 1000, 10);
@@ -25,8 +28,9 @@ fn test_payout() {
 	let initial_balance_alice = contract.get_fdai_balance(alice());
 	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
 
-    contract.finalize_market(0, Some(0));
+    contract.finalize_market(0, Some(vec![100, 0, 0, 0]));
 	contract.claim_earnings(0, carol());
+	testing_env!(get_context(alice(), market_end_timestamp_ns() + 1800000000000));
 	contract.claim_earnings(0, alice());
 
 	let claimable_after_claim_carol = contract.get_claimable(0, carol());
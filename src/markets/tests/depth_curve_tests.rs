@@ -0,0 +1,61 @@
+use super::*;
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 1, 2400, 20);
+	contract.place_order(0, 1, 1000, 10);
+	contract.place_order(0, 2, 4500, 30);
+	contract.place_order(0, 2, 2500, 25);
+	return contract;
+}
+
+#[test]
+fn test_depth_curve() {
+	let contract = init_tests();
+	assert_eq!(contract.get_depth_curve(0, 0), vec![
+		DepthPoint { price: 50, shares: 120, spend: 6000 },
+		DepthPoint { price: 60, shares: 150, spend: 7800 },
+		DepthPoint { price: 65, shares: 220, spend: 12350 },
+	]);
+}
+
+#[test]
+fn test_quote_partial_level() {
+	let contract = init_tests();
+	assert_eq!(contract.quote(0, 0, 7000), Quote { shares: 136, spend: 6960, average_price: 51, worst_price: 60 });
+}
+
+#[test]
+fn test_quote_exceeding_depth() {
+	let contract = init_tests();
+	assert_eq!(contract.quote(0, 0, 100000), Quote { shares: 220, spend: 12350, average_price: 56, worst_price: 65 });
+}
+
+#[test]
+fn test_quote_without_liquidity() {
+	let contract = init_tests();
+	assert_eq!(contract.quote(0, 0, 10), Quote { shares: 0, spend: 0, average_price: 0, worst_price: 0 });
+	assert_eq!(contract.get_depth_curve(0, 1).len(), 2);
+	assert_eq!(contract.quote(0, 1, 20000).worst_price, 75);
+}
+
+#[test]
+fn test_quote_matches_fill() {
+	let mut contract = init_tests();
+	let quote = contract.quote(0, 0, 7000);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order_with_options(0, 0, 7000, quote.worst_price, OrderOptions { self_trade_prevention: None, order_type: Some(market::order_type::OrderType::ImmediateOrCancel) });
+
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - quote.spend);
+	assert_eq!(contract.get_market(0).liquidity, quote.shares * 100);
+	assert_eq!(contract.get_depth_curve(0, 0)[0], DepthPoint { price: 60, shares: 14, spend: 840 });
+}
@@ -0,0 +1,93 @@
+use super::*;
+
+fn reporting_duration() -> u64 {
+	return time::HOUR;
+}
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	contract.set_creation_bond(to_dai(2));
+	contract.set_default_window_config(WindowConfig {
+		grace_period: 0,
+		designated_reporting_period: reporting_duration(),
+		dispute_window: 30 * time::MINUTE,
+	});
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 4, outcome_tags(4), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	return contract;
+}
+
+#[test]
+fn test_creation_bond_escrow() {
+	let contract = init_tests();
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - to_dai(2));
+	assert_eq!(contract.get_market(0).designated_reporter, carol());
+}
+
+#[test]
+#[should_panic(expected = "only the designated reporter can resolute during the designated reporting window")]
+fn test_report_during_designated_window() {
+	let mut contract = init_tests();
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(5));
+}
+
+#[test]
+fn test_designated_report_without_bond() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0, 0, 0]), 0);
+
+	assert_eq!(contract.get_market_status(0), MarketStatus::Disputing(1));
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100));
+}
+
+#[test]
+fn test_missed_designated_window() {
+	let mut contract = init_tests();
+	testing_env!(get_context(alice(), market_end_timestamp_ns() + time::ms_to_ns(reporting_duration())));
+	contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(5));
+
+	assert_eq!(contract.get_market_status(0), MarketStatus::Disputing(1));
+	assert_eq!(contract.get_fdai_balance(alice()), to_dai(100) - to_dai(5) + to_dai(2));
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - to_dai(2));
+}
+
+#[test]
+fn test_custom_designated_reporter() {
+	let mut contract = init_tests();
+	contract.create_market_with_options("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string(), MarketOptions { designated_reporter: Some(alice()), ..MarketOptions::default() });
+
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	contract.resolute_market(1, Some(vec![0, 100]), 0);
+
+	assert_eq!(contract.get_market_status(1), MarketStatus::Disputing(1));
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - to_dai(2));
+}
+
+#[test]
+fn test_designated_report_above_bond() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_designated_reporter_bond(to_dai(1));
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	let balance_before = contract.get_fdai_balance(carol());
+	contract.resolute_market(1, Some(vec![0, 100]), to_dai(5));
+
+	// Only the designated bond is staked, the rest and the creation bond come back
+	let market = contract.get_market(1);
+	let window = &market.resolution_windows[0];
+	assert_eq!(window.participants_to_outcome_to_stake.get(&carol()).unwrap().values().sum::<u128>(), to_dai(1));
+	assert_eq!(window.staked_per_outcome.values().sum::<u128>(), to_dai(1));
+	assert_eq!(contract.get_fdai_balance(carol()), balance_before - to_dai(1) + to_dai(2));
+}
@@ -0,0 +1,90 @@
+use super::*;
+
+// Carol's order 0 on outcome 0 is filled for 60 shares with 2000 left, her order 1 on outcome 1 is unfilled
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.place_order(0, 0, 5000, 50);
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 1, 3000, 50);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(0, 1, 1000, 20);
+	return contract;
+}
+
+fn resolute(contract: &mut Markets) {
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+}
+
+fn finalize(contract: &mut Markets) {
+	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
+	contract.finalize_market(0, None);
+}
+
+#[test]
+fn test_cancel_after_end_time() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	let balance = contract.get_fdai_balance(carol());
+	contract.cancel_order(0, 1, 1);
+	assert_eq!(contract.get_fdai_balance(carol()), balance + 1000);
+}
+
+#[test]
+fn test_cancel_after_resolution() {
+	let mut contract = init_tests();
+	resolute(&mut contract);
+	let claimable = contract.get_claimable(0, carol());
+	let balance = contract.get_fdai_balance(carol());
+
+	contract.cancel_order(0, 1, 1);
+	contract.cancel_order(0, 0, 0);
+	assert_eq!(contract.get_fdai_balance(carol()), balance + 3000);
+	assert_eq!(contract.get_claimable(0, carol()), claimable - 3000);
+
+	finalize(&mut contract);
+	contract.claim_earnings(0, carol());
+	assert_eq!(contract.get_fdai_balance(carol()), balance + claimable);
+}
+
+#[test]
+fn test_cancel_after_finalization() {
+	let mut contract = init_tests();
+	resolute(&mut contract);
+	finalize(&mut contract);
+	let claimable = contract.get_claimable(0, carol());
+	let balance = contract.get_fdai_balance(carol());
+
+	contract.cancel_order(0, 0, 0);
+	assert_eq!(contract.get_claimable(0, carol()), claimable - 2000);
+	contract.claim_earnings(0, carol());
+	assert_eq!(contract.get_fdai_balance(carol()), balance + claimable);
+}
+
+#[test]
+fn test_claim_takes_orders_off_the_book() {
+	let mut contract = init_tests();
+	resolute(&mut contract);
+	finalize(&mut contract);
+	contract.claim_earnings(0, carol());
+	assert_eq!(contract.get_orderbook(0, 0, 10), vec![]);
+	assert_eq!(contract.get_orderbook(0, 1, 10), vec![]);
+}
+
+#[test]
+#[should_panic(expected = "order isn't open")]
+fn test_cancel_after_claim() {
+	let mut contract = init_tests();
+	resolute(&mut contract);
+	finalize(&mut contract);
+	contract.claim_earnings(0, carol());
+	contract.cancel_order(0, 1, 1);
+}
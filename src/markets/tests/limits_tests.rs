@@ -0,0 +1,130 @@
+use super::*;
+
+fn limits(max_open_orders: u64, max_position: u128, min_order_size: u128) -> OrderLimits {
+	return OrderLimits {
+		max_open_orders,
+		max_position,
+		min_order_size,
+	};
+}
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	return contract;
+}
+
+fn set_order_limits(contract: &mut Markets, order_limits: OrderLimits) {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_order_limits(order_limits);
+	testing_env!(get_context(carol(), current_block_timestamp()));
+}
+
+#[test]
+fn test_default_order_limits() {
+	let contract = init_tests();
+	assert_eq!(contract.get_order_limits(None), OrderLimits::default());
+	assert_eq!(contract.get_order_limits(Some(0)), OrderLimits::default());
+}
+
+#[test]
+#[should_panic(expected = "order is below the minimum size")]
+fn test_min_order_size() {
+	let mut contract = init_tests();
+	set_order_limits(&mut contract, limits(0, 0, 1000));
+	contract.place_order(0, 0, 999, 50);
+}
+
+#[test]
+fn test_min_order_size_after_rounding() {
+	let mut contract = init_tests();
+	set_order_limits(&mut contract, limits(0, 0, 1000));
+	contract.place_order(0, 0, 1000, 50);
+	assert_eq!(contract.get_open_orders(0, 0).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "too many open orders in this market")]
+fn test_max_open_orders() {
+	let mut contract = init_tests();
+	set_order_limits(&mut contract, limits(2, 0, 0));
+	contract.place_order(0, 0, 1000, 50);
+	contract.place_order(0, 1, 1000, 20);
+	contract.place_order(0, 0, 1000, 40);
+}
+
+#[test]
+fn test_max_open_orders_per_market() {
+	let mut contract = init_tests();
+	set_order_limits(&mut contract, limits(2, 0, 0));
+	contract.place_order(0, 0, 1000, 50);
+	contract.place_order(0, 0, 1000, 40);
+	contract.place_order(1, 0, 1000, 40);
+
+	contract.cancel_order(0, 0, 0);
+	contract.place_order(0, 0, 1000, 30);
+	assert_eq!(contract.get_open_orders(0, 0).len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "position limit exceeded")]
+fn test_max_position() {
+	let mut contract = init_tests();
+	set_order_limits(&mut contract, limits(0, 100, 0));
+	contract.place_order(0, 0, 2500, 50);
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 1, 2500, 50);
+
+	// 50 shares filled and 40 open, 20 more would cross the limit
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(0, 0, 2000, 50);
+	contract.place_order(0, 0, 1000, 50);
+}
+
+#[test]
+fn test_max_position_per_outcome() {
+	let mut contract = init_tests();
+	set_order_limits(&mut contract, limits(0, 100, 0));
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 1, 1000, 10);
+	assert_eq!(contract.get_market(0).get_position_for(&carol(), 0), 100);
+	assert_eq!(contract.get_market(0).get_position_for(&carol(), 1), 100);
+}
+
+#[test]
+fn test_market_order_limits_override() {
+	let mut contract = init_tests();
+	set_order_limits(&mut contract, limits(0, 0, 5000));
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_market_order_limits(1, Some(limits(0, 0, 100)));
+	assert_eq!(contract.get_order_limits(Some(1)), limits(0, 0, 100));
+	assert_eq!(contract.get_order_limits(Some(0)), limits(0, 0, 5000));
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(1, 0, 1000, 50);
+	assert_eq!(contract.get_open_orders(1, 0).len(), 1);
+
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_market_order_limits(1, None);
+	assert_eq!(contract.get_order_limits(Some(1)), limits(0, 0, 5000));
+}
+
+#[test]
+#[should_panic(expected = "only the owner can update the order limits")]
+fn test_set_order_limits_without_role() {
+	let mut contract = init_tests();
+	contract.set_order_limits(limits(0, 0, 0));
+}
+
+#[test]
+#[should_panic(expected = "only market moderators can update market order limits")]
+fn test_set_market_order_limits_without_role() {
+	let mut contract = init_tests();
+	contract.set_market_order_limits(0, None);
+}
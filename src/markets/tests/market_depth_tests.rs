@@ -4,6 +4,8 @@ use super::*;
 fn test_liquidity_for_price() {
 	testing_env!(get_context(carol(), current_block_timestamp()));
 	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
 
@@ -43,6 +45,7 @@ fn test_liquidity_for_price() {
 fn test_valid_binary_market_depth() {
 	testing_env!(get_context(carol(), current_block_timestamp()));
 	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test.com".to_string());
 
@@ -50,6 +53,7 @@ fn test_valid_binary_market_depth() {
 	contract.place_order(0, 0, 6000, 60);
 
 	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.place_order(0, 1, 2000, 20);
 	contract.place_order(0, 1, 3000, 30);
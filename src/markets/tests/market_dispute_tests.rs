@@ -3,6 +3,8 @@ use super::*;
 fn init_tests() -> Markets {
 	testing_env!(get_context(carol(), current_block_timestamp()));
 	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 4, outcome_tags(4), categories(), market_end_timestamp_ms(), 4, 2, "test".to_string());
 	return contract;
@@ -16,16 +18,17 @@ fn test_dispute_valid() {
 	contract.place_order(0, 3, to_dai(1), 10);
 
 	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.place_order(0, 1, to_dai(1), 10);
 	contract.place_order(0, 2, to_dai(1), 10);
 
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-    contract.resolute_market(0, Some(0), to_dai(5));
+    contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(5));
     testing_env!(get_context(alice(), market_end_timestamp_ns()));
-	contract.dispute_market(0, Some(1), to_dai(10));
+	contract.dispute_market(0, Some(vec![0, 100, 0, 0]), to_dai(10));
     testing_env!(get_context(judge(), market_end_timestamp_ns()));
-    contract.finalize_market(0, Some(0));
+    contract.finalize_market(0, Some(vec![100, 0, 0, 0]));
 
 
     let claimable_carol = contract.get_claimable(0, carol()) ;
@@ -37,7 +40,9 @@ fn test_dispute_valid() {
 	let fdai_before_claim_alice = contract.get_fdai_balance(alice());
 	let fdai_before_claim_carol = contract.get_fdai_balance(carol());
 	
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
 	contract.claim_earnings(0, carol());
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
 	contract.claim_earnings(0, alice());
 	
 	let fdai_after_claim_alice = contract.get_fdai_balance(alice());
@@ -54,7 +59,7 @@ fn test_dispute_valid() {
 #[should_panic(expected = "market isn't resoluted yet")]
 fn test_market_not_resoluted() {
 	let mut contract = init_tests();
-	contract.dispute_market(0, Some(0), to_dai(5));
+	contract.dispute_market(0, Some(vec![100, 0, 0, 0]), to_dai(5));
 }
 
 #[test]
@@ -62,11 +67,11 @@ fn test_market_not_resoluted() {
 fn test_finalized_market() {
 	let mut contract = init_tests();
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.resolute_market(0, Some(0), to_dai(5));
+	contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(5));
 	testing_env!(get_context(judge(), market_end_timestamp_ns() + 1800000000000));
 	contract.finalize_market(0, None);
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.dispute_market(0, Some(1), to_dai(5));
+	contract.dispute_market(0, Some(vec![0, 100, 0, 0]), to_dai(5));
 }
 
 #[test]
@@ -74,7 +79,7 @@ fn test_finalized_market() {
 fn test_market_finalization_pre_dispute_window_close() {
 	let mut contract = init_tests();
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-    contract.resolute_market(0, Some(0), to_dai(5));
+    contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(5));
 	contract.finalize_market(0, None);
 }
 
@@ -83,7 +88,7 @@ fn test_market_finalization_pre_dispute_window_close() {
 fn test_dispute_after_dispute_window() {
 	let mut contract = init_tests();
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.resolute_market(0, Some(0), to_dai(5));
+	contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(5));
 	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800100000000));
 	contract.dispute_market(0, None, to_dai(5));
 }
@@ -93,18 +98,18 @@ fn test_dispute_after_dispute_window() {
 fn test_finalize_as_not_owner() {
 	let mut contract = init_tests();
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.resolute_market(0, Some(0), to_dai(5));
+	contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(5));
 	contract.dispute_market(0, None, to_dai(10));
 	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
 	contract.finalize_market(0, None);
 }
 
 #[test]
-#[should_panic(expected = "invalid winning outcome")]
+#[should_panic(expected = "invalid payout numerator")]
 fn test_invalid_dispute_outcome() {
 	let mut contract = init_tests();
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.resolute_market(0, Some(4), to_dai(5));
+	contract.resolute_market(0, Some(vec![0, 0, 0, 0, 100]), to_dai(5));
 }
 
 #[test]
@@ -112,8 +117,8 @@ fn test_invalid_dispute_outcome() {
 fn test_dispute_with_same_outcome() {
 	let mut contract = init_tests();
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.resolute_market(0, Some(3), to_dai(5));
-	contract.dispute_market(0, Some(3), to_dai(10));
+	contract.resolute_market(0, Some(vec![0, 0, 0, 100]), to_dai(5));
+	contract.dispute_market(0, Some(vec![0, 0, 0, 100]), to_dai(10));
 }
 
 #[test]
@@ -121,9 +126,9 @@ fn test_dispute_with_same_outcome() {
 fn test_dispute_escalation_failure() {
 	let mut contract = init_tests();
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.resolute_market(0, Some(3), to_dai(5));
-	contract.dispute_market(0, Some(2), to_dai(10));
-	contract.dispute_market(0, Some(3), to_dai(20));
+	contract.resolute_market(0, Some(vec![0, 0, 0, 100]), to_dai(5));
+	contract.dispute_market(0, Some(vec![0, 0, 100, 0]), to_dai(10));
+	contract.dispute_market(0, Some(vec![0, 0, 0, 100]), to_dai(20));
 }
 
 #[test]
@@ -134,7 +139,7 @@ fn test_stake_refund() {
 	let pre_resolution_balance = contract.get_fdai_balance(carol());
 	let post_resolution_expected_balance = pre_resolution_balance - to_dai(5);
 	
-	contract.resolute_market(0, Some(3), to_dai(7));
+	contract.resolute_market(0, Some(vec![0, 0, 0, 100]), to_dai(7));
 
 	let post_resolution_balance = contract.get_fdai_balance(carol());
 
@@ -142,7 +147,7 @@ fn test_stake_refund() {
 
 	let expected_post_dispute_balance = post_resolution_balance - to_dai(10);
 
-	contract.dispute_market(0, Some(1), to_dai(15));
+	contract.dispute_market(0, Some(vec![0, 100, 0, 0]), to_dai(15));
 
 	let post_dispute_balance = contract.get_fdai_balance(carol());
 
@@ -154,7 +159,7 @@ fn test_stake_refund() {
 fn test_insufficient_balance() {
 	let mut contract = init_tests();
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.resolute_market(0, Some(3), to_dai(101));
+	contract.resolute_market(0, Some(vec![0, 0, 0, 100]), to_dai(101));
 
 }
 
@@ -165,7 +170,7 @@ fn test_fee_claim() {
 	contract.place_order(0, 0, to_dai(1), 10);
 	contract.place_order(0, 1, to_dai(9), 90);
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.resolute_market(0, Some(0), to_dai(5));
+	contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(5));
 	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
 	contract.finalize_market(0, None);
 
@@ -188,26 +193,27 @@ fn test_cancel_dispute_participation() {
 	contract.place_order(0, 3, to_dai(1), 10);
 
 	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	register_storage(&mut contract, alice(), market_end_timestamp_ns());
 	contract.claim_fdai();
-	contract.resolute_market(0, Some(1), to_dai(4));
+	contract.resolute_market(0, Some(vec![0, 100, 0, 0]), to_dai(4));
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-    contract.resolute_market(0, Some(0), to_dai(5));
+    contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(5));
 	testing_env!(get_context(alice(), market_end_timestamp_ns()));
-	contract.dispute_market(0, Some(1), to_dai(10));
+	contract.dispute_market(0, Some(vec![0, 100, 0, 0]), to_dai(10));
     testing_env!(get_context(judge(), market_end_timestamp_ns()));
-	contract.finalize_market(0, Some(0));
+	contract.finalize_market(0, Some(vec![100, 0, 0, 0]));
 	
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
 	contract.claim_earnings(0, alice());
 
 	let fdai_before_withdrawl_alice = contract.get_fdai_balance(alice());
 
-	testing_env!(get_context(alice(), market_end_timestamp_ns()));
-	contract.withdraw_dispute_stake(0, 0, Some(1));
+	contract.withdraw_dispute_stake(0, 0, Some(vec![0, 100, 0, 0]));
 	let fdai_after_withdrawl_alice = contract.get_fdai_balance(alice());
 	assert_eq!(fdai_after_withdrawl_alice, fdai_before_withdrawl_alice + to_dai(4));
 
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.withdraw_dispute_stake(0, 1, Some(0));
+	contract.withdraw_dispute_stake(0, 1, Some(vec![100, 0, 0, 0]));
 	
 }
 
@@ -216,24 +222,25 @@ fn test_crowdsourced_dispute_resolution() {
 	let mut contract = init_tests();
 
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.resolute_market(0, Some(0), to_dai(3));
+	contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(3));
 	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	register_storage(&mut contract, alice(), market_end_timestamp_ns());
 	contract.claim_fdai();
-	contract.resolute_market(0, Some(0), to_dai(2));
+	contract.resolute_market(0, Some(vec![100, 0, 0, 0]), to_dai(2));
 	
 	let resolution_window_0 = contract.get_active_resolution_window(0);
 	assert_eq!(resolution_window_0.expect("None value instead of 1st dispute window").round, 1);
 
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.dispute_market(0, Some(1), to_dai(5));
+	contract.dispute_market(0, Some(vec![0, 100, 0, 0]), to_dai(5));
 	testing_env!(get_context(alice(), market_end_timestamp_ns()));
-	contract.dispute_market(0, Some(1), to_dai(5));
+	contract.dispute_market(0, Some(vec![0, 100, 0, 0]), to_dai(5));
 
 	let resolution_window_1 = contract.get_active_resolution_window(0);
 	assert_eq!(resolution_window_1.expect("None value instead of 2nd dispute window").round, 2);
 
 	testing_env!(get_context(judge(), market_end_timestamp_ns()));
-	contract.finalize_market(0, Some(0));
+	contract.finalize_market(0, Some(vec![100, 0, 0, 0]));
 
 
 	let claimable_carol = contract.get_claimable(0, carol()) ;
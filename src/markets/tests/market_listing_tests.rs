@@ -0,0 +1,74 @@
+use super::*;
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), vec!["sports".to_string()], market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), vec!["crypto".to_string()], market_end_timestamp_ms() + 1000, 0, 0, "test".to_string());
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), vec!["sports".to_string(), "crypto".to_string()], market_end_timestamp_ms() + 2000, 0, 0, "test".to_string());
+	contract.create_market("Hi!".to_string(), empty_string(), 4, outcome_tags(4), categories(), market_end_timestamp_ms() + 3000, 0, 0, "test".to_string());
+	return contract;
+}
+
+fn ids(
+	markets: Vec<MarketSummary>
+) -> Vec<u64> {
+	return markets.iter().map(|market| market.id).collect();
+}
+
+#[test]
+fn test_pagination() {
+	let contract = init_tests();
+	assert_eq!(ids(contract.get_markets(0, 2, None)), vec![0, 1]);
+	assert_eq!(ids(contract.get_markets(2, 2, None)), vec![2, 3]);
+	assert_eq!(ids(contract.get_markets(4, 2, None)), Vec::<u64>::new());
+}
+
+#[test]
+fn test_category_and_creator_filters() {
+	let contract = init_tests();
+	let sports = MarketFilter { category: Some("sports".to_string()), ..MarketFilter::default() };
+	let by_alice = MarketFilter { creator: Some(alice()), ..MarketFilter::default() };
+	let crypto_by_carol = MarketFilter { category: Some("crypto".to_string()), creator: Some(carol()), ..MarketFilter::default() };
+
+	assert_eq!(ids(contract.get_markets(0, 10, Some(sports.clone()))), vec![0, 2]);
+	assert_eq!(ids(contract.get_markets(1, 10, Some(sports))), vec![2]);
+	assert_eq!(ids(contract.get_markets(0, 10, Some(by_alice))), vec![2, 3]);
+	assert_eq!(ids(contract.get_markets(0, 10, Some(crypto_by_carol))), vec![1]);
+}
+
+#[test]
+fn test_end_time_and_outcome_filters() {
+	let contract = init_tests();
+	let end_time_range = MarketFilter { end_time_from: Some(market_end_timestamp_ms() + 1000), end_time_to: Some(market_end_timestamp_ms() + 2000), ..MarketFilter::default() };
+	let binary = MarketFilter { outcomes: Some(2), ..MarketFilter::default() };
+
+	assert_eq!(ids(contract.get_markets(0, 10, Some(end_time_range))), vec![1, 2]);
+	assert_eq!(ids(contract.get_markets(0, 10, Some(binary))), vec![0, 2]);
+}
+
+#[test]
+fn test_status_filter() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+
+	let disputing = MarketFilter { status: Some(MarketStatus::Disputing(0)), ..MarketFilter::default() };
+	let open = MarketFilter { status: Some(MarketStatus::Open), ..MarketFilter::default() };
+
+	assert_eq!(ids(contract.get_markets(0, 10, Some(disputing))), vec![0]);
+	assert_eq!(ids(contract.get_markets(0, 10, Some(open))), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_specific_markets() {
+	let contract = init_tests();
+	let markets = contract.get_specific_markets(vec![1, 3]);
+	assert_eq!(markets.keys().cloned().collect::<Vec<u64>>(), vec![1, 3]);
+}
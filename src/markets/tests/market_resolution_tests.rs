@@ -4,6 +4,8 @@ use super::*;
 fn test_invalid_market_payout_calc() {
 	testing_env!(get_context(carol(), current_block_timestamp()));
 	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 4, outcome_tags(4), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
 
@@ -13,6 +15,7 @@ fn test_invalid_market_payout_calc() {
 	contract.place_order(0, 3, 1000, 10);
 
 	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
 	contract.claim_fdai();
 
 	contract.place_order(0, 0, 6000, 60);
@@ -53,18 +56,21 @@ fn test_invalid_market_payout_calc() {
 fn test_valid_market_payout_calc() {
 	testing_env!(get_context(carol(), current_block_timestamp()));
 	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.create_market("Hi!".to_string(), empty_string(), 4, outcome_tags(4), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
 
 	contract.place_order(0, 0, 7000, 70);
 
 	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
 	contract.claim_fdai();
 	contract.place_order(0, 1, 1000, 10);
 	contract.place_order(0, 2, 2000, 20);
 
 	testing_env!(get_context(carol(), market_end_timestamp_ns()));
-	contract.resolute_market(0, Some(1), to_dai(5));
+	contract.resolute_market(0, Some(vec![0, 100, 0, 0]), to_dai(5));
 
 	let open_orders_0 = contract.get_open_orders(0, 0);
 	let open_orders_1 = contract.get_open_orders(0, 1);
@@ -88,3 +94,40 @@ fn test_valid_market_payout_calc() {
 	assert_eq!(claimable_carol, to_dai(5));
 	assert_eq!(claimable_alice, 10000);
 }
+
+#[test]
+fn test_split_market_payout_calc() {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+
+	contract.place_order(0, 0, 5000, 50);
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 1, 5000, 50);
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![50, 50]), to_dai(5));
+	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
+	contract.finalize_market(0, Some(vec![50, 50]));
+
+	let claimable_carol = contract.get_claimable(0, carol());
+	assert_eq!(claimable_carol, 5000 + to_dai(5));
+}
+
+#[test]
+#[should_panic(expected = "payout numerator should sum to 100")]
+fn test_payout_numerator_sum() {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![50, 40]), to_dai(5));
+}
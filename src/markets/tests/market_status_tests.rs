@@ -0,0 +1,120 @@
+use super::*;
+
+fn all_statuses() -> Vec<MarketStatus> {
+	return vec![
+		MarketStatus::Pending,
+		MarketStatus::Open,
+		MarketStatus::Closed,
+		MarketStatus::Reporting,
+		MarketStatus::Disputing(1),
+		MarketStatus::Disputing(2),
+		MarketStatus::AwaitingJudge,
+		MarketStatus::Finalized(Some(vec![100, 0])),
+		MarketStatus::Finalized(None),
+		MarketStatus::Voided,
+	];
+}
+
+fn is_legal(
+	from: &MarketStatus,
+	to: &MarketStatus
+) -> bool {
+	return match (from, to) {
+		(MarketStatus::Pending, MarketStatus::Open) => true,
+		(MarketStatus::Open, MarketStatus::Closed) => true,
+		(MarketStatus::Closed, MarketStatus::Reporting) => true,
+		(MarketStatus::Reporting, MarketStatus::Disputing(1)) => true,
+		(MarketStatus::Disputing(1), MarketStatus::Disputing(2)) => true,
+		(MarketStatus::Disputing(_), MarketStatus::AwaitingJudge) => true,
+		(MarketStatus::Disputing(_), MarketStatus::Finalized(_)) => true,
+		(MarketStatus::AwaitingJudge, MarketStatus::Finalized(_)) => true,
+		(MarketStatus::Pending, MarketStatus::Voided) => true,
+		(MarketStatus::Open, MarketStatus::Voided) => true,
+		(MarketStatus::Closed, MarketStatus::Voided) => true,
+		(MarketStatus::Reporting, MarketStatus::Voided) => true,
+		(MarketStatus::Disputing(_), MarketStatus::Voided) => true,
+		(MarketStatus::AwaitingJudge, MarketStatus::Voided) => true,
+		_ => false
+	};
+}
+
+#[test]
+fn test_all_status_transitions() {
+	for from in all_statuses() {
+		for to in all_statuses() {
+			assert_eq!(from.can_transition_to(&to), is_legal(&from, &to), "unexpected transition rule from {:?} to {:?}", from, to);
+		}
+	}
+}
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	return contract;
+}
+
+#[test]
+fn test_undisputed_market_lifecycle() {
+	let mut contract = init_tests();
+	assert_eq!(contract.get_market_status(0), MarketStatus::Open);
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	assert_eq!(contract.get_market_status(0), MarketStatus::Reporting);
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	assert_eq!(contract.get_market_status(0), MarketStatus::Disputing(1));
+	assert_eq!(contract.get_market(0).status, MarketStatus::Disputing(1));
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
+	contract.finalize_market(0, None);
+	assert_eq!(contract.get_market_status(0), MarketStatus::Finalized(Some(vec![100, 0])));
+}
+
+#[test]
+fn test_disputed_market_lifecycle() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	contract.dispute_market(0, Some(vec![0, 100]), to_dai(10));
+	assert_eq!(contract.get_market_status(0), MarketStatus::AwaitingJudge);
+
+	testing_env!(get_context(judge(), market_end_timestamp_ns()));
+	contract.finalize_market(0, Some(vec![50, 50]));
+	assert_eq!(contract.get_market_status(0), MarketStatus::Finalized(Some(vec![50, 50])));
+}
+
+#[test]
+fn test_closed_during_grace_period() {
+	let mut contract = init_tests();
+	let window_config = WindowConfig {
+		grace_period: time::HOUR,
+		designated_reporting_period: 0,
+		dispute_window: 30 * time::MINUTE,
+	};
+	contract.create_market_with_options("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string(), MarketOptions { window_config: Some(window_config), ..MarketOptions::default() });
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	assert_eq!(contract.get_market_status(1), MarketStatus::Closed);
+	assert_eq!(contract.get_market_timeline(1).status, MarketStatus::Closed);
+}
+
+#[test]
+#[should_panic(expected = "market is already finalized")]
+fn test_finalize_twice() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
+	contract.finalize_market(0, None);
+	contract.finalize_market(0, Some(vec![0, 100]));
+}
+
+#[test]
+#[should_panic(expected = "market isn't open for trading")]
+fn test_order_after_end_time() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.place_order(0, 0, 5000, 50);
+}
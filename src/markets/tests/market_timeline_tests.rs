@@ -0,0 +1,64 @@
+use super::*;
+
+fn window_config() -> WindowConfig {
+	return WindowConfig {
+		grace_period: time::HOUR,
+		designated_reporting_period: 0,
+		dispute_window: 2 * time::HOUR,
+	};
+}
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.create_market_with_options("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string(), MarketOptions { window_config: Some(window_config()), ..MarketOptions::default() });
+	return contract;
+}
+
+#[test]
+fn test_time_conversion() {
+	assert_eq!(time::ms_to_ns(market_end_timestamp_ms()), market_end_timestamp_ns());
+	assert_eq!(time::ns_to_ms(market_end_timestamp_ns()), market_end_timestamp_ms());
+}
+
+#[test]
+fn test_default_timeline() {
+	let mut contract = init_tests();
+	let timeline = contract.get_market_timeline(0);
+	assert_eq!(timeline.end_time, market_end_timestamp_ms());
+	assert_eq!(timeline.reporting_start_time, market_end_timestamp_ms());
+	assert_eq!(timeline.designated_reporting_end_time, market_end_timestamp_ms());
+	assert_eq!(timeline.resolution_window_end_time, None);
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+
+	let timeline = contract.get_market_timeline(0);
+	assert_eq!(timeline.resolution_window_round, 1);
+	assert_eq!(timeline.resolution_window_end_time, Some(market_end_timestamp_ms() + 30 * time::MINUTE));
+}
+
+#[test]
+#[should_panic(expected = "reporting hasn't opened yet")]
+fn test_report_during_grace_period() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(1, Some(vec![100, 0]), to_dai(5));
+}
+
+#[test]
+fn test_configured_windows() {
+	let mut contract = init_tests();
+	let timeline = contract.get_market_timeline(1);
+	assert_eq!(timeline.reporting_start_time, market_end_timestamp_ms() + time::HOUR);
+
+	let reporting_start_ns = time::ms_to_ns(timeline.reporting_start_time);
+	testing_env!(get_context(carol(), reporting_start_ns));
+	contract.resolute_market(1, Some(vec![100, 0]), to_dai(5));
+
+	let timeline = contract.get_market_timeline(1);
+	assert_eq!(timeline.resolution_window_end_time, Some(market_end_timestamp_ms() + 3 * time::HOUR));
+}
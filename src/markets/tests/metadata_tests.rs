@@ -0,0 +1,165 @@
+use super::*;
+
+fn metadata() -> MarketMetadata {
+	return MarketMetadata {
+		title: "Will it rain tomorrow?".to_string(),
+		resolution_criteria: "Resolves YES if the weather service reports rain".to_string(),
+		source_urls: vec!["https://weather.example.com".to_string()],
+		outcome_labels: vec!["NO".to_string(), "YES".to_string()],
+		image_hash: None,
+		language: "en".to_string(),
+	};
+}
+
+fn init_tests(metadata: Option<MarketMetadata>) -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market_with_options("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string(), MarketOptions { metadata, ..MarketOptions::default() });
+	return contract;
+}
+
+#[test]
+fn test_create_market_with_metadata() {
+	let contract = init_tests(Some(metadata()));
+	assert_eq!(contract.get_market(0).metadata, metadata());
+}
+
+#[test]
+fn test_legacy_metadata() {
+	let mut contract = init_tests(None);
+	let market_metadata = contract.get_market(0).metadata;
+	assert_eq!(market_metadata.title, "Hi!".to_string());
+	assert_eq!(market_metadata.outcome_labels, vec!["NO".to_string(), "YES".to_string()]);
+	assert_eq!(market_metadata.language, "en".to_string());
+
+	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	assert_eq!(contract.get_market(1).metadata.outcome_labels, outcome_tags(3));
+}
+
+#[test]
+#[should_panic(expected = "outcome label can't be empty")]
+fn test_empty_outcome_tags() {
+	let mut contract = init_tests(None);
+	contract.create_market("Hi!".to_string(), empty_string(), 3, vec![empty_string(), "a".to_string(), "b".to_string()], categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+}
+
+#[test]
+#[should_panic(expected = "duplicate outcome label")]
+fn test_duplicate_outcome_labels() {
+	let mut market_metadata = metadata();
+	market_metadata.outcome_labels = vec!["Yes".to_string(), "YES".to_string()];
+	init_tests(Some(market_metadata));
+}
+
+#[test]
+#[should_panic(expected = "outcome labels should match the number of outcomes")]
+fn test_outcome_labels_count() {
+	let mut market_metadata = metadata();
+	market_metadata.outcome_labels.push("MAYBE".to_string());
+	init_tests(Some(market_metadata));
+}
+
+#[test]
+#[should_panic(expected = "outcome labels should match the outcome tags")]
+fn test_outcome_labels_differ_from_tags() {
+	let mut contract = init_tests(None);
+	let mut market_metadata = metadata();
+	market_metadata.outcome_labels = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+	contract.create_market_with_options("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string(), MarketOptions { metadata: Some(market_metadata), ..MarketOptions::default() });
+}
+
+#[test]
+fn test_categorical_metadata() {
+	let mut contract = init_tests(None);
+	let mut market_metadata = metadata();
+	market_metadata.outcome_labels = outcome_tags(3);
+	contract.create_market_with_options("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string(), MarketOptions { metadata: Some(market_metadata.clone()), ..MarketOptions::default() });
+	assert_eq!(contract.get_market(1).metadata, market_metadata);
+}
+
+#[test]
+#[should_panic(expected = "outcome label is too long")]
+fn test_outcome_label_too_long() {
+	let mut market_metadata = metadata();
+	market_metadata.outcome_labels[1] = "Y".repeat(market::metadata::MAX_OUTCOME_LABEL_LENGTH + 1);
+	init_tests(Some(market_metadata));
+}
+
+#[test]
+#[should_panic(expected = "title can't be empty")]
+fn test_empty_title() {
+	let mut market_metadata = metadata();
+	market_metadata.title = " ".to_string();
+	init_tests(Some(market_metadata));
+}
+
+#[test]
+#[should_panic(expected = "source url should be an http(s) url")]
+fn test_invalid_source_url() {
+	let mut market_metadata = metadata();
+	market_metadata.source_urls = vec!["javascript:alert(1)".to_string()];
+	init_tests(Some(market_metadata));
+}
+
+#[test]
+#[should_panic(expected = "image hash should be 64 hex characters")]
+fn test_invalid_image_hash() {
+	let mut market_metadata = metadata();
+	market_metadata.image_hash = Some("not a hash".to_string());
+	init_tests(Some(market_metadata));
+}
+
+#[test]
+#[should_panic(expected = "language should be a two letter ISO 639-1 code")]
+fn test_invalid_language() {
+	let mut market_metadata = metadata();
+	market_metadata.language = "english".to_string();
+	init_tests(Some(market_metadata));
+}
+
+#[test]
+#[should_panic(expected = "description is too long")]
+fn test_description_too_long() {
+	let mut contract = init_tests(None);
+	contract.create_market_with_options("a".repeat(market::metadata::MAX_TEXT_LENGTH + 1), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string(), MarketOptions { metadata: Some(metadata()), ..MarketOptions::default() });
+}
+
+#[test]
+fn test_long_legacy_description() {
+	let mut contract = init_tests(None);
+	let description = "é".repeat(market::metadata::MAX_TEXT_LENGTH / 2);
+	contract.create_market(description, empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	assert_eq!(contract.get_market(1).metadata.title, "é".repeat(market::metadata::MAX_TITLE_LENGTH / 2));
+}
+
+#[test]
+fn test_update_metadata_before_trades() {
+	let mut contract = init_tests(Some(metadata()));
+	contract.update_market_metadata(0, Some("Will it rain on Monday?".to_string()), Some("ab".repeat(32)), Some("nl".to_string()));
+
+	let market_metadata = contract.get_market(0).metadata;
+	assert_eq!(market_metadata.title, "Will it rain on Monday?".to_string());
+	assert_eq!(market_metadata.image_hash, Some("ab".repeat(32)));
+	assert_eq!(market_metadata.language, "nl".to_string());
+	assert_eq!(market_metadata.outcome_labels, metadata().outcome_labels);
+}
+
+#[test]
+#[should_panic(expected = "only the creator can edit the market")]
+fn test_update_metadata_by_other_account() {
+	let mut contract = init_tests(Some(metadata()));
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	contract.update_market_metadata(0, Some("Hi!".to_string()), None, None);
+}
+
+#[test]
+#[should_panic(expected = "market has already been traded")]
+fn test_update_metadata_after_trade() {
+	let mut contract = init_tests(Some(metadata()));
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 1, 5000, 50);
+	contract.update_market_metadata(0, Some("Hi!".to_string()), None, None);
+}
@@ -0,0 +1,189 @@
+use super::*;
+use borsh::{BorshDeserialize, BorshSerialize};
+use migration::{MarketsV1, MarketV1, ResolutionWindowV1};
+use market::orderbook::Orderbook;
+
+fn v1_window(round: u64, end_time: u64) -> ResolutionWindowV1 {
+	return ResolutionWindowV1 {
+		round,
+		participants_to_outcome_to_stake: HashMap::new(),
+		required_bond_size: to_dai(5) * (round as u128 + 1),
+		staked_per_outcome: HashMap::new(),
+		end_time,
+		outcome: None,
+	};
+}
+
+fn v1_market(id: u64, categories: Vec<String>) -> MarketV1 {
+	let mut orderbooks = BTreeMap::new();
+	for outcome in 0..2 {
+		orderbooks.insert(outcome, Orderbook::new(outcome));
+	}
+	return MarketV1 {
+		id,
+		description: "Hi!".to_string(),
+		extra_info: empty_string(),
+		creator: carol(),
+		outcomes: 2,
+		outcome_tags: outcome_tags(2),
+		categories,
+		last_price_for_outcomes: HashMap::new(),
+		creation_time: market_creation_timestamp(),
+		end_time: market_end_timestamp_ms(),
+		orderbooks,
+		winning_outcome: None,
+		resoluted: false,
+		resolute_bond: to_dai(5),
+		liquidity: 0,
+		disputed: false,
+		finalized: false,
+		fee_claimed: false,
+		fee_percentage: 1,
+		cost_percentage: 0,
+		api_source: "test".to_string(),
+		resolution_windows: vec![v1_window(0, market_end_timestamp_ms())],
+	};
+}
+
+// A finalized market carol traded and reported on, and an open market in a category
+fn v1_fixture() -> MarketsV1 {
+	let mut finalized_market = v1_market(0, categories());
+	finalized_market.orderbooks.get_mut(&0).unwrap().place_order(carol(), 0, 5000, 100, 50, 0, 0);
+	finalized_market.winning_outcome = Some(0);
+	finalized_market.resoluted = true;
+	finalized_market.finalized = true;
+	let resolution_window = &mut finalized_market.resolution_windows[0];
+	resolution_window.outcome = Some(0);
+	resolution_window.staked_per_outcome.insert(0, to_dai(5));
+	resolution_window.participants_to_outcome_to_stake.insert(carol(), vec![(0, to_dai(5))].into_iter().collect());
+	finalized_market.resolution_windows.push(v1_window(1, market_end_timestamp_ms() + 1800000));
+
+	let mut active_markets = BTreeMap::new();
+	active_markets.insert(0, finalized_market);
+	active_markets.insert(1, v1_market(1, vec!["sports".to_string()]));
+
+	let mut fdai_balances = HashMap::new();
+	fdai_balances.insert(carol(), to_dai(90));
+
+	return MarketsV1 {
+		creator: judge(),
+		active_markets,
+		nonce: 2,
+		fdai_balances,
+		fdai_circulation: to_dai(100),
+		fdai_in_protocol: to_dai(10),
+		fdai_outside_escrow: to_dai(90),
+		user_count: 1,
+		max_fee_percentage: 5,
+		creation_bond: 0,
+	};
+}
+
+// v2 state with every field that was added since v1 set to something other than its default
+fn v2_fixture() -> Vec<u8> {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(2), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.grant_role(bob(), Role::Judge);
+	contract.set_judge_threshold(2);
+	contract.pause(Some(0), Some(Action::Claiming));
+	contract.set_self_trade_prevention(SelfTradePrevention::DecrementBoth);
+	contract.set_order_limits(OrderLimits { max_open_orders: 5, max_position: 0, min_order_size: 100 });
+	contract.set_allowed_categories(vec!["sports".to_string()]);
+	contract.set_allowed_tags(vec!["home".to_string(), "away".to_string()]);
+	contract.approve_operator(bob(), Some(0), Some(6000));
+	return contract.try_to_vec().unwrap();
+}
+
+fn store_state(state: Vec<u8>) {
+	env::storage_write(b"STATE", &state);
+}
+
+#[test]
+fn test_migrate_v1_state() {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	store_state(v1_fixture().try_to_vec().unwrap());
+	let contract = Markets::migrate();
+
+	assert_eq!(contract.state_version, migration::STATE_VERSION);
+	assert_eq!(contract.nonce, 2);
+	assert_eq!(contract.get_owner(), judge());
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(90));
+
+	assert_eq!(contract.get_market_status(0), MarketStatus::Finalized(Some(vec![100, 0])));
+	assert_eq!(contract.get_market_status(1), MarketStatus::Open);
+	let market = contract.get_market(0);
+	assert_eq!(market.finalization_time, Some(market_end_timestamp_ms() + 1800000));
+	assert_eq!(market.designated_reporter, carol());
+	assert_eq!(market.resolution_windows[0].outcome, Some(vec![100, 0]));
+	assert_eq!(market.resolution_windows[0].staked_per_outcome.get("100:0"), Some(&to_dai(5)));
+	assert_eq!(contract.get_open_orders(0, 0).len(), 1);
+}
+
+#[test]
+fn test_migrate_rebuilds_indexes() {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	store_state(v1_fixture().try_to_vec().unwrap());
+	let contract = Markets::migrate();
+
+	let filter = MarketFilter { category: Some("sports".to_string()), ..MarketFilter::default() };
+	let sports_markets: Vec<u64> = contract.get_markets(0, 10, Some(filter)).iter().map(|market| market.id).collect();
+	assert_eq!(sports_markets, vec![1]);
+	assert_eq!(contract.get_markets(0, 10, Some(MarketFilter { creator: Some(carol()), ..MarketFilter::default() })).len(), 2);
+	let portfolio: Vec<u64> = contract.get_portfolio(carol()).iter().map(|entry| entry.market_id).collect();
+	assert_eq!(portfolio, vec![0]);
+}
+
+#[test]
+fn test_migrated_market_can_be_claimed() {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	store_state(v1_fixture().try_to_vec().unwrap());
+	let mut contract = Markets::migrate();
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let claimable = contract.get_claimable(0, carol());
+	assert_eq!(claimable, 5000 + to_dai(5));
+	contract.claim_earnings(0, carol());
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(90) + claimable);
+}
+
+#[test]
+#[should_panic(expected = "only the owner can migrate")]
+fn test_migrate_without_permission() {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	store_state(v1_fixture().try_to_vec().unwrap());
+	Markets::migrate();
+}
+
+#[test]
+#[should_panic(expected = "state is already up to date")]
+fn test_migrate_current_state() {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	store_state(Markets::default().try_to_vec().unwrap());
+	Markets::migrate();
+}
+
+#[test]
+fn test_load_v2_state() {
+	let state = v2_fixture();
+	assert_eq!(u32::try_from_slice(&state[0..4]).unwrap(), migration::STATE_VERSION);
+	assert!(MarketsV1::try_from_slice(&state).is_err());
+
+	let contract = Markets::try_from_slice(&state).unwrap();
+	assert_eq!(contract.get_judge_threshold(), 2);
+	assert!(contract.has_role(bob(), Role::Judge));
+	assert!(contract.get_pause_flags(Some(0)).claiming);
+	assert_eq!(contract.get_self_trade_prevention(), SelfTradePrevention::DecrementBoth);
+	assert_eq!(contract.get_order_limits(None), OrderLimits { max_open_orders: 5, max_position: 0, min_order_size: 100 });
+	assert_eq!(contract.allowed_categories, vec!["sports".to_string()]);
+	assert_eq!(contract.allowed_tags, vec!["home".to_string(), "away".to_string()]);
+	assert_eq!(contract.get_operator_approval(judge(), bob()).unwrap().spend_limit, Some(6000));
+	assert_eq!(contract.get_market(0).metadata.title, "Hi!".to_string());
+}
+
+#[test]
+#[should_panic(expected = "state is already up to date")]
+fn test_migrate_v2_state() {
+	store_state(v2_fixture());
+	Markets::migrate();
+}
@@ -0,0 +1,29 @@
+use super::*;
+
+// Stand-in for an oracle contract, answers get_outcome the way a deployed oracle would
+pub struct MockOracle {
+	reports: HashMap<String, OracleReport>,
+}
+
+impl MockOracle {
+	pub fn new() -> Self {
+		Self {
+			reports: HashMap::new(),
+		}
+	}
+
+	pub fn report(
+		&mut self,
+		request_id: String,
+		payout_numerator: Option<Vec<u128>>
+	) {
+		self.reports.insert(request_id, OracleReport { payout_numerator });
+	}
+
+	pub fn get_outcome(
+		&self,
+		request_id: String
+	) -> Option<OracleReport> {
+		return self.reports.get(&request_id).cloned();
+	}
+}
@@ -0,0 +1,148 @@
+use super::*;
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	contract.set_moderation(true);
+	contract.set_creation_bond(to_dai(1));
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), vec!["sports".to_string()], market_end_timestamp_ms(), 0, 0, "test".to_string());
+	return contract;
+}
+
+#[test]
+fn test_market_pending_when_moderated() {
+	let contract = init_tests();
+	assert_eq!(contract.get_market_status(0), MarketStatus::Pending);
+}
+
+#[test]
+#[should_panic(expected = "market isn't open for trading")]
+fn test_order_on_pending_market() {
+	let mut contract = init_tests();
+	contract.place_order(0, 0, 5000, 50);
+}
+
+#[test]
+fn test_approve_market() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.approve_market(0);
+	assert_eq!(contract.get_market_status(0), MarketStatus::Open);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(0, 0, 5000, 50);
+	assert_eq!(contract.get_market_status(0), MarketStatus::Open);
+}
+
+#[test]
+#[should_panic(expected = "only market moderators can approve markets")]
+fn test_approve_market_without_role() {
+	let mut contract = init_tests();
+	contract.approve_market(0);
+}
+
+#[test]
+#[should_panic(expected = "market isn't pending approval")]
+fn test_approve_market_twice() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.approve_market(0);
+	contract.approve_market(0);
+}
+
+#[test]
+fn test_reject_market_refunds_creation_bond() {
+	let mut contract = init_tests();
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(99));
+
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.reject_market(0);
+	assert_eq!(contract.get_market_status(0), MarketStatus::Voided);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100));
+}
+
+#[test]
+#[should_panic(expected = "market isn't pending approval")]
+fn test_reject_approved_market() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.approve_market(0);
+	contract.reject_market(0);
+}
+
+#[test]
+fn test_markets_open_without_moderation() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_moderation(false);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	assert_eq!(contract.get_market_status(1), MarketStatus::Open);
+}
+
+#[test]
+#[should_panic(expected = "category isn't allowed")]
+fn test_category_not_allowed() {
+	let mut contract = init_tests();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), vec!["gossip".to_string()], market_end_timestamp_ms(), 0, 0, "test".to_string());
+}
+
+#[test]
+#[should_panic(expected = "duplicate category")]
+fn test_duplicate_category() {
+	let mut contract = init_tests();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), vec!["crypto".to_string(), "crypto".to_string()], market_end_timestamp_ms(), 0, 0, "test".to_string());
+}
+
+#[test]
+fn test_set_allowed_categories() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_allowed_categories(vec!["gossip".to_string()]);
+	assert_eq!(contract.get_allowed_categories(), &vec!["gossip".to_string()]);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), vec!["gossip".to_string()], market_end_timestamp_ms(), 0, 0, "test".to_string());
+}
+
+#[test]
+#[should_panic(expected = "tag isn't allowed")]
+fn test_tag_not_allowed() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_allowed_tags(vec!["outcome 0".to_string(), "outcome 1".to_string()]);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+}
+
+#[test]
+#[should_panic(expected = "duplicate tag")]
+fn test_duplicate_tag() {
+	let mut contract = init_tests();
+	contract.create_market("Hi!".to_string(), empty_string(), 3, vec!["a".to_string(), "b".to_string(), "a".to_string()], categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+}
+
+#[test]
+fn test_set_allowed_tags() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_allowed_tags(outcome_tags(3));
+	assert_eq!(contract.get_allowed_tags(), &outcome_tags(3));
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	assert_eq!(contract.get_market_status(1), MarketStatus::Pending);
+}
+
+#[test]
+#[should_panic(expected = "only market moderators can update the allowed tags")]
+fn test_set_allowed_tags_without_role() {
+	let mut contract = init_tests();
+	contract.set_allowed_tags(vec![]);
+}
@@ -0,0 +1,134 @@
+use super::*;
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.approve_operator(bob(), Some(0), Some(6000));
+	return contract;
+}
+
+#[test]
+fn test_operator_places_and_cancels_orders() {
+	let mut contract = init_tests();
+	let initial_balance = contract.get_fdai_balance(alice());
+
+	testing_env!(get_context(bob(), current_block_timestamp()));
+	contract.place_order_for(alice(), 0, 0, 5000, 50);
+	assert_eq!(contract.get_fdai_balance(alice()), initial_balance - 5000);
+	assert_eq!(contract.get_open_orders(0, 0).get(&0).unwrap().creator, alice());
+	assert_eq!(contract.get_operator_approval(alice(), bob()).unwrap().spent, 5000);
+
+	contract.cancel_order_for(alice(), 0, 0, 0);
+	assert_eq!(contract.get_fdai_balance(alice()), initial_balance);
+	assert_eq!(contract.get_operator_approval(alice(), bob()).unwrap().spent, 0);
+}
+
+#[test]
+fn test_operator_refunds_are_credited() {
+	let mut contract = init_tests();
+	testing_env!(get_context(bob(), current_block_timestamp()));
+	contract.place_order_with_options_for(alice(), 0, 0, 5000, 50, OrderOptions { self_trade_prevention: None, order_type: Some(market::order_type::OrderType::ImmediateOrCancel) });
+	assert_eq!(contract.get_operator_approval(alice(), bob()).unwrap().spent, 0);
+
+	// The whole allowance is available again once the order is cancelled
+	contract.place_order_for(alice(), 0, 0, 5000, 50);
+	contract.cancel_order_for(alice(), 0, 0, 0);
+	contract.place_order_for(alice(), 0, 0, 6000, 50);
+	assert_eq!(contract.get_operator_approval(alice(), bob()).unwrap().spent, 6000);
+}
+
+#[test]
+#[should_panic(expected = "operator spend limit exceeded")]
+fn test_operator_spend_limit() {
+	let mut contract = init_tests();
+	testing_env!(get_context(bob(), current_block_timestamp()));
+	contract.place_order_for(alice(), 0, 0, 5000, 50);
+	contract.place_order_for(alice(), 0, 0, 1500, 50);
+}
+
+#[test]
+#[should_panic(expected = "operator isn't approved for this market")]
+fn test_operator_market_scope() {
+	let mut contract = init_tests();
+	testing_env!(get_context(bob(), current_block_timestamp()));
+	contract.place_order_for(alice(), 1, 0, 1000, 50);
+}
+
+#[test]
+#[should_panic(expected = "not authorized to act for this account")]
+fn test_order_without_approval() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order_for(alice(), 0, 0, 1000, 50);
+}
+
+#[test]
+#[should_panic(expected = "not authorized to act for this account")]
+fn test_revoked_operator() {
+	let mut contract = init_tests();
+	contract.revoke_operator(bob());
+	assert_eq!(contract.get_operator_approval(alice(), bob()), None);
+
+	testing_env!(get_context(bob(), current_block_timestamp()));
+	contract.place_order_for(alice(), 0, 0, 1000, 50);
+}
+
+#[test]
+#[should_panic(expected = "not authorized to act for this account")]
+fn test_cancel_order_without_approval() {
+	let mut contract = init_tests();
+	contract.place_order(0, 0, 1000, 50);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.cancel_order_for(alice(), 0, 0, 0);
+}
+
+fn finalize_market(contract: &mut Markets) {
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 1, 5000, 50);
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	testing_env!(get_context(alice(), market_end_timestamp_ns() + 1800000000000));
+	contract.finalize_market(0, None);
+}
+
+#[test]
+fn test_operator_claims_earnings() {
+	let mut contract = init_tests();
+	finalize_market(&mut contract);
+	let claimable = contract.get_claimable(0, alice());
+	let initial_balance = contract.get_fdai_balance(alice());
+
+	testing_env!(get_context(bob(), market_end_timestamp_ns() + 1800000000000));
+	contract.claim_earnings(0, alice());
+	assert_eq!(contract.get_fdai_balance(alice()), initial_balance + claimable);
+}
+
+#[test]
+#[should_panic(expected = "not authorized to act for this account")]
+fn test_claim_earnings_for_other_account() {
+	let mut contract = init_tests();
+	finalize_market(&mut contract);
+	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
+	contract.claim_earnings(0, alice());
+}
+
+#[test]
+fn test_claim_all_without_approval() {
+	let mut contract = init_tests();
+	finalize_market(&mut contract);
+	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
+	let results = contract.claim_all(alice(), vec![0]);
+	assert_eq!(results[0].error, Some("not authorized to act for this account".to_string()));
+	assert!(contract.get_claimable(0, alice()) > 0);
+}
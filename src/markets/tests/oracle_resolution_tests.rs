@@ -0,0 +1,171 @@
+use super::*;
+use super::mock_oracle::MockOracle;
+use near_vm_logic::types::PromiseResult as VmPromiseResult;
+type OracleConfig = oracle::OracleConfig;
+
+fn oracle_config() -> OracleConfig {
+	return OracleConfig {
+		contract_id: "oracle.near".to_string(),
+		request_id: "request_0".to_string(),
+	};
+}
+
+// The oracle's response to get_outcome, as on_oracle_outcome receives it
+fn oracle_response(oracle: &MockOracle) -> VmPromiseResult {
+	return VmPromiseResult::Successful(serde_json::to_vec(&oracle.get_outcome("request_0".to_string())).unwrap());
+}
+
+// Switches to the contract calling itself back with promise_result as the result of the get_outcome promise, get_context's current account is alice
+fn set_promise_result(predecessor_account_id: String, promise_result: VmPromiseResult) {
+	let storage = match env::take_blockchain_interface() {
+		Some(mut blockchain) => blockchain.as_mut_mocked_blockchain().unwrap().take_storage(),
+		None => Default::default()
+	};
+	let context = get_context(predecessor_account_id, market_end_timestamp_ns());
+	env::set_blockchain_interface(Box::new(MockedBlockchain::new(context, Default::default(), Default::default(), vec![promise_result], storage)));
+}
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market_with_options("Hi!".to_string(), empty_string(), 4, outcome_tags(4), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string(), MarketOptions { oracle: Some(oracle_config()), ..MarketOptions::default() });
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	return contract;
+}
+
+#[test]
+fn test_oracle_resolution() {
+	let mut contract = init_tests();
+	let mut oracle = MockOracle::new();
+	oracle.report("request_0".to_string(), Some(vec![0, 100, 0, 0]));
+
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	let balance_before_request = contract.get_fdai_balance(alice());
+	contract.request_resolution(0);
+	assert_eq!(contract.get_fdai_balance(alice()), balance_before_request - to_dai(5));
+
+	set_promise_result(alice(), oracle_response(&oracle));
+	contract.on_oracle_outcome(0, alice());
+
+	let market = contract.get_market(0);
+	assert_eq!(market.status, MarketStatus::Disputing(1));
+	assert_eq!(market.payout_numerator, Some(vec![0, 100, 0, 0]));
+	assert_eq!(contract.get_active_resolution_window(0).unwrap().round, 1);
+
+	// Normal dispute window still applies
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.dispute_market(0, Some(vec![100, 0, 0, 0]), to_dai(10));
+	assert_eq!(contract.get_active_resolution_window(0).unwrap().round, 2);
+}
+
+#[test]
+fn test_unanswered_oracle_request_refunds_bond() {
+	let mut contract = init_tests();
+	let oracle = MockOracle::new();
+
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	let balance_before_request = contract.get_fdai_balance(alice());
+	contract.request_resolution(0);
+	set_promise_result(alice(), oracle_response(&oracle));
+	contract.on_oracle_outcome(0, alice());
+
+	assert_eq!(contract.get_fdai_balance(alice()), balance_before_request);
+	assert_eq!(contract.get_market_status(0), MarketStatus::Reporting);
+	assert_eq!(contract.get_market(0).pending_oracle_request, None);
+}
+
+#[test]
+fn test_failed_oracle_call_refunds_bond() {
+	let mut contract = init_tests();
+
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	let balance_before_request = contract.get_fdai_balance(alice());
+	contract.request_resolution(0);
+	set_promise_result(alice(), VmPromiseResult::Failed);
+	contract.on_oracle_outcome(0, alice());
+
+	assert_eq!(contract.get_fdai_balance(alice()), balance_before_request);
+	assert_eq!(contract.get_market_status(0), MarketStatus::Reporting);
+	assert_eq!(contract.get_market(0).pending_oracle_request, None);
+
+	// The request can be made again once the bond is back
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	contract.request_resolution(0);
+	assert_eq!(contract.get_fdai_balance(alice()), balance_before_request - to_dai(5));
+}
+
+#[test]
+fn test_malformed_oracle_response_refunds_bond() {
+	let mut contract = init_tests();
+
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	let balance_before_request = contract.get_fdai_balance(alice());
+	contract.request_resolution(0);
+	set_promise_result(alice(), VmPromiseResult::Successful(b"not a report".to_vec()));
+	contract.on_oracle_outcome(0, alice());
+
+	assert_eq!(contract.get_fdai_balance(alice()), balance_before_request);
+	assert_eq!(contract.get_market_status(0), MarketStatus::Reporting);
+}
+
+#[test]
+#[should_panic(expected = "only the contract can call this method")]
+fn test_oracle_callback_from_other_account() {
+	let mut contract = init_tests();
+	let mut oracle = MockOracle::new();
+	oracle.report("request_0".to_string(), Some(vec![0, 100, 0, 0]));
+
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	contract.request_resolution(0);
+	set_promise_result(carol(), oracle_response(&oracle));
+	contract.on_oracle_outcome(0, alice());
+}
+
+#[test]
+#[should_panic(expected = "no pending oracle request for this account")]
+fn test_oracle_callback_without_request() {
+	let mut contract = init_tests();
+	let mut oracle = MockOracle::new();
+	oracle.report("request_0".to_string(), Some(vec![0, 100, 0, 0]));
+
+	set_promise_result(alice(), oracle_response(&oracle));
+	contract.on_oracle_outcome(0, alice());
+}
+
+#[test]
+fn test_invalid_oracle_payout_refunds_bond() {
+	let mut contract = init_tests();
+	let mut oracle = MockOracle::new();
+	oracle.report("request_0".to_string(), Some(vec![50, 0, 0]));
+
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	let balance_before_request = contract.get_fdai_balance(alice());
+	contract.request_resolution(0);
+	set_promise_result(alice(), oracle_response(&oracle));
+	contract.on_oracle_outcome(0, alice());
+
+	assert_eq!(contract.get_fdai_balance(alice()), balance_before_request);
+	assert_eq!(contract.get_market_status(0), MarketStatus::Reporting);
+}
+
+#[test]
+#[should_panic(expected = "resolution has already been requested")]
+fn test_duplicate_resolution_request() {
+	let mut contract = init_tests();
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	contract.request_resolution(0);
+	contract.request_resolution(0);
+}
+
+#[test]
+#[should_panic(expected = "market has no oracle")]
+fn test_request_resolution_without_oracle() {
+	let mut contract = init_tests();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	contract.request_resolution(1);
+}
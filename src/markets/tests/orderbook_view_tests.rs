@@ -0,0 +1,91 @@
+use super::*;
+
+fn level(price: u128, shares: u128, orders: u64) -> PriceLevel {
+	return PriceLevel { price, shares, orders };
+}
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 1, 2000, 20);
+	contract.place_order(0, 1, 1000, 10);
+	contract.place_order(0, 2, 4500, 30);
+	contract.place_order(0, 2, 2500, 25);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(0, 1, 400, 20);
+	return contract;
+}
+
+#[test]
+fn test_orderbook_levels() {
+	let contract = init_tests();
+	assert_eq!(contract.get_orderbook(0, 1, 10), vec![level(20, 120, 2), level(10, 100, 1)]);
+	assert_eq!(contract.get_orderbook(0, 1, 1), vec![level(20, 120, 2)]);
+	assert_eq!(contract.get_orderbook(0, 0, 10), vec![]);
+}
+
+#[test]
+fn test_orderbook_levels_after_cancel() {
+	let mut contract = init_tests();
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	contract.cancel_order(0, 1, 0);
+	assert_eq!(contract.get_orderbook(0, 1, 10), vec![level(20, 20, 1), level(10, 100, 1)]);
+	contract.cancel_order(0, 1, 1);
+	assert_eq!(contract.get_orderbook(0, 1, 10), vec![level(20, 20, 1)]);
+}
+
+#[test]
+fn test_implied_book() {
+	let contract = init_tests();
+	assert_eq!(contract.get_implied_book(0, 0, 10), vec![level(50, 120, 3), level(60, 30, 1), level(65, 70, 1)]);
+	assert_eq!(contract.get_implied_book(0, 0, 2), vec![level(50, 120, 3), level(60, 30, 1)]);
+}
+
+#[test]
+fn test_implied_book_skips_empty_orderbooks() {
+	let contract = init_tests();
+	assert_eq!(contract.get_implied_book(0, 1, 10), vec![level(70, 150, 1), level(75, 100, 1)]);
+}
+
+#[test]
+fn test_implied_book_without_bids() {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(2), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	assert_eq!(contract.get_implied_book(0, 0, 10), vec![]);
+}
+
+#[test]
+fn test_levels_match_fill_order() {
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.place_order(0, 0, 4000, 40);
+	contract.place_order(0, 0, 3000, 60);
+
+	let levels = contract.get_orderbook(0, 0, 10);
+	let implied_levels = contract.get_implied_book(0, 1, 10);
+	assert_eq!(levels, vec![level(60, 50, 1), level(40, 100, 1)]);
+	assert_eq!(implied_levels, vec![level(40, 50, 1), level(60, 100, 1)]);
+
+	// Buying exactly the first implied level takes out the first level of the snapshot and nothing else
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 1, implied_levels[0].shares * implied_levels[0].price, implied_levels[0].price);
+
+	assert_eq!(contract.get_orderbook(0, 0, 10), vec![levels[1].clone()]);
+	assert_eq!(contract.get_filled_orders(0, 0).get(&1).unwrap().price, levels[0].price);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100) - implied_levels[0].shares * implied_levels[0].price);
+	assert_eq!(contract.get_market_price(0, 1), 100 - levels[1].price);
+}
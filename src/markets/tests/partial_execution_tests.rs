@@ -0,0 +1,105 @@
+use super::*;
+
+const LEVELS: u128 = 60;
+
+// Alice bids 10 shares of outcome 0 at each price from 10 up to 10 + LEVELS, every fill takes out a single level starting at the highest
+fn init_tests() -> Markets {
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	for price in 10..10 + LEVELS {
+		contract.place_order(0, 0, price * 10, price);
+	}
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	return contract;
+}
+
+#[test]
+fn test_matching_is_capped() {
+	let mut contract = init_tests();
+	let unfilled = contract.place_order(0, 1, 100000, 90);
+
+	let max_levels = market::MAX_MATCH_ITERATIONS as u128;
+	assert_eq!(contract.get_open_orders(0, 0).len() as u128, LEVELS - max_levels);
+	assert_eq!(contract.get_market(0).liquidity, max_levels * 10 * 100);
+	assert_eq!(contract.get_market_price(0, 1), 100 - (10 + LEVELS - max_levels - 1));
+
+	// What's left would still cross the book so it's refunded instead of resting
+	assert_eq!(contract.get_open_orders(0, 1).len(), 0);
+	assert_eq!(contract.get_market_price(0, 0), 100);
+	let spent = contract.get_market(0).orderbooks.get(&1).unwrap().get_spend_by(carol());
+	assert_eq!(unfilled, 99990 - spent);
+	assert_eq!(contract.get_fdai_balance(carol()) + spent, to_dai(100));
+}
+
+#[test]
+fn test_capped_categorical_order_is_refunded() {
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 3, outcome_tags(3), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	for price in 10..10 + LEVELS {
+		contract.place_order(0, 1, price * 10, price);
+	}
+	contract.place_order(0, 2, 5000, 5);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	let unfilled = contract.place_order(0, 0, 100000, 90);
+
+	// Resting at 90 would push the other outcomes' bids over 100
+	let max_levels = market::MAX_MATCH_ITERATIONS as u128;
+	assert!(unfilled > 0);
+	assert_eq!(contract.get_open_orders(0, 0).len(), 0);
+	assert_eq!(contract.get_orderbook(0, 0, 10).len(), 0);
+	assert_eq!(contract.get_market_price(0, 1), 95);
+	assert_eq!(contract.get_market_price(0, 2), 100 - (10 + LEVELS - max_levels - 1));
+
+	// Placing what was refunded again matches the levels that are left and rests the rest
+	assert_eq!(contract.place_order(0, 0, unfilled, 90), 0);
+	assert_eq!(contract.get_open_orders(0, 1).len(), 0);
+	assert_eq!(contract.get_orderbook(0, 0, 10)[0].price, 90);
+	assert_eq!(contract.get_market_price(0, 2), 10);
+}
+
+#[test]
+fn test_immediate_or_cancel_refunds_leftover() {
+	let mut contract = init_tests();
+	contract.place_order_with_options(0, 1, 100000, 90, OrderOptions { self_trade_prevention: None, order_type: Some(market::order_type::OrderType::ImmediateOrCancel) });
+
+	let max_levels = market::MAX_MATCH_ITERATIONS as u128;
+	assert_eq!(contract.get_open_orders(0, 1).len(), 0);
+	assert_eq!(contract.get_open_orders(0, 0).len() as u128, LEVELS - max_levels);
+
+	// Only what was filled is spent
+	let market = contract.get_market(0);
+	let spent = market.orderbooks.get(&1).unwrap().get_spend_by(carol());
+	assert_eq!(market.liquidity, max_levels * 10 * 100);
+	assert!(spent < 99990);
+	assert_eq!(contract.get_fdai_balance(carol()) + spent, to_dai(100));
+}
+
+#[test]
+fn test_immediate_or_cancel_without_match() {
+	let mut contract = init_tests();
+	let storage_available = contract.storage_balance_of(carol()).unwrap().available;
+	contract.place_order_with_options(0, 1, 1000, 20, OrderOptions { self_trade_prevention: None, order_type: Some(market::order_type::OrderType::ImmediateOrCancel) });
+	assert_eq!(contract.get_open_orders(0, 1).len(), 0);
+	assert_eq!(contract.get_filled_orders(0, 1).len(), 0);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100));
+	assert_eq!(contract.storage_balance_of(carol()).unwrap().available, storage_available);
+}
+
+#[test]
+fn test_leftover_rests_without_cross() {
+	let mut contract = init_tests();
+	assert_eq!(contract.place_order(0, 1, 1000, 20), 0);
+	assert_eq!(contract.get_orderbook(0, 1, 10)[0].price, 20);
+}
@@ -0,0 +1,141 @@
+use super::*;
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	contract.grant_role(bob(), Role::Pauser);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	return contract;
+}
+
+fn pause_as(contract: &mut Markets, account_id: String, market_id: Option<u64>, action: Option<Action>) {
+	testing_env!(get_context(account_id, current_block_timestamp()));
+	contract.pause(market_id, action);
+	testing_env!(get_context(carol(), current_block_timestamp()));
+}
+
+#[test]
+#[should_panic(expected = "trading is paused")]
+fn test_global_trading_pause() {
+	let mut contract = init_tests();
+	pause_as(&mut contract, judge(), None, Some(Action::Trading));
+	contract.place_order(1, 0, 5000, 50);
+}
+
+#[test]
+#[should_panic(expected = "trading is paused")]
+fn test_market_pause() {
+	let mut contract = init_tests();
+	pause_as(&mut contract, bob(), Some(0), None);
+	contract.place_order(1, 0, 5000, 50);
+	assert_eq!(contract.get_pause_flags(Some(0)).all, true);
+	assert_eq!(contract.get_pause_flags(None).all, false);
+	contract.place_order(0, 0, 5000, 50);
+}
+
+#[test]
+fn test_unpause() {
+	let mut contract = init_tests();
+	pause_as(&mut contract, bob(), None, None);
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.unpause(None, None);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(0, 0, 5000, 50);
+	assert_eq!(contract.get_open_orders(0, 0).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "only pausers can pause")]
+fn test_pause_without_permission() {
+	let mut contract = init_tests();
+	pause_as(&mut contract, alice(), None, None);
+}
+
+#[test]
+fn test_cancel_while_paused() {
+	let mut contract = init_tests();
+	let initial_balance = contract.get_fdai_balance(carol());
+	contract.place_order(0, 0, 5000, 50);
+	pause_as(&mut contract, judge(), None, None);
+	contract.cancel_order(0, 0, 0);
+	assert_eq!(contract.get_fdai_balance(carol()), initial_balance);
+}
+
+#[test]
+#[should_panic(expected = "reporting is paused")]
+fn test_reporting_pause() {
+	let mut contract = init_tests();
+	pause_as(&mut contract, judge(), Some(0), Some(Action::Reporting));
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+}
+
+fn finalize_market(contract: &mut Markets) {
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 1, 5000, 50);
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
+	contract.finalize_market(0, None);
+}
+
+#[test]
+#[should_panic(expected = "claiming is paused")]
+fn test_claiming_pause() {
+	let mut contract = init_tests();
+	pause_as(&mut contract, judge(), None, Some(Action::Claiming));
+	finalize_market(&mut contract);
+
+	let results = contract.claim_all(carol(), vec![0]);
+	assert_eq!(results[0].error, Some("claiming is paused".to_string()));
+	contract.claim_earnings(0, carol());
+}
+
+#[test]
+#[should_panic(expected = "trading is paused")]
+fn test_circuit_breaker() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_circuit_breaker(10, time::HOUR);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 1, 5000, 50);
+	assert_eq!(contract.get_pause_flags(Some(0)).trading, false);
+
+	// Moves the price of both outcomes by 20
+	contract.place_order(0, 0, 3000, 30);
+	contract.place_order(0, 1, 7000, 70);
+	assert_eq!(contract.get_pause_flags(Some(0)).trading, true);
+	contract.place_order(1, 0, 5000, 50);
+	contract.place_order(0, 0, 5000, 50);
+}
+
+#[test]
+fn test_circuit_breaker_ignores_trades_before_unpause() {
+	let mut contract = init_tests();
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.set_circuit_breaker(10, time::HOUR);
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 1, 5000, 50);
+	contract.place_order(0, 0, 3000, 30);
+	contract.place_order(0, 1, 7000, 70);
+	assert_eq!(contract.get_pause_flags(Some(0)).trading, true);
+
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	contract.unpause(Some(0), Some(Action::Trading));
+
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(0, 0, 1000, 10);
+	assert_eq!(contract.get_pause_flags(Some(0)).trading, false);
+	assert_eq!(contract.get_open_orders(0, 0).len(), 1);
+}
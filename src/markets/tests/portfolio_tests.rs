@@ -0,0 +1,60 @@
+use super::*;
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 1, 2000, 40);
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 1, 2500, 50);
+	contract.place_order(1, 0, 1000, 10);
+	return contract;
+}
+
+#[test]
+fn test_portfolio_positions() {
+	let contract = init_tests();
+	let portfolio = contract.get_portfolio(carol());
+	assert_eq!(portfolio.len(), 1);
+
+	let entry = &portfolio[0];
+	assert_eq!(entry.market_id, 0);
+	assert_eq!(entry.open_order_value, 4500);
+	assert_eq!(entry.shares_by_outcome.get(&0), Some(&50));
+	assert_eq!(entry.shares_by_outcome.get(&1), Some(&0));
+	assert_eq!(entry.stake_by_round, vec![0]);
+	assert_eq!(entry.claimable, 0);
+	// 50 shares valued at 60 plus the open orders minus everything spent
+	assert_eq!(entry.unrealized_pnl, 500);
+}
+
+#[test]
+fn test_portfolio_tracks_every_market() {
+	let contract = init_tests();
+	let market_ids: Vec<u64> = contract.get_portfolio(alice()).iter().map(|entry| entry.market_id).collect();
+	assert_eq!(market_ids, vec![0, 1]);
+	assert_eq!(contract.get_portfolio(bob()).len(), 0);
+}
+
+#[test]
+fn test_portfolio_stakes_and_claims() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	let stake_by_round = &contract.get_portfolio(carol())[0].stake_by_round;
+	assert_eq!(stake_by_round.iter().sum::<u128>(), to_dai(5));
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
+	contract.finalize_market(0, None);
+	assert_eq!(contract.get_portfolio(carol())[0].claimable, contract.get_claimable(0, carol()));
+
+	contract.claim_earnings(0, carol());
+	assert_eq!(contract.get_portfolio(carol()).len(), 0);
+}
@@ -0,0 +1,159 @@
+use super::*;
+
+fn as_account(account_id: String) {
+	testing_env!(get_context(account_id, current_block_timestamp()));
+}
+
+fn init_tests() -> Markets {
+	as_account(carol());
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	return contract;
+}
+
+// Resoluted and disputed so the market waits for the judges
+fn await_judge(contract: &mut Markets) {
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	contract.dispute_market(0, None, to_dai(10));
+	assert_eq!(contract.get_market_status(0), MarketStatus::AwaitingJudge);
+}
+
+fn vote(contract: &mut Markets, judge_id: String, payout_numerator: Option<Vec<u128>>) {
+	testing_env!(get_context(judge_id, market_end_timestamp_ns()));
+	contract.finalize_market(0, payout_numerator);
+}
+
+#[test]
+fn test_initial_roles() {
+	let contract = init_tests();
+	assert_eq!(contract.get_roles(judge()), roles::all_roles());
+	assert_eq!(contract.get_roles(carol()), vec![]);
+	assert_eq!(contract.get_role_members(Role::Owner), vec![judge()]);
+}
+
+#[test]
+fn test_grant_and_revoke_role() {
+	let mut contract = init_tests();
+	as_account(judge());
+	contract.grant_role(bob(), Role::Judge);
+	assert!(contract.has_role(bob(), Role::Judge));
+	assert_eq!(contract.get_role_members(Role::Judge), vec![bob(), judge()]);
+
+	contract.revoke_role(bob(), Role::Judge);
+	assert!(!contract.has_role(bob(), Role::Judge));
+	assert_eq!(contract.get_roles(bob()), vec![]);
+}
+
+#[test]
+#[should_panic(expected = "only the owner can grant roles")]
+fn test_grant_role_without_permission() {
+	let mut contract = init_tests();
+	contract.grant_role(carol(), Role::Owner);
+}
+
+#[test]
+#[should_panic(expected = "can't revoke the last owner")]
+fn test_revoke_last_owner() {
+	let mut contract = init_tests();
+	as_account(judge());
+	contract.revoke_role(judge(), Role::Owner);
+}
+
+#[test]
+#[should_panic(expected = "only minters can mint fdai")]
+fn test_mint_without_role() {
+	let mut contract = init_tests();
+	contract.add_to_creators_funds(to_dai(10));
+}
+
+#[test]
+fn test_market_moderator() {
+	let mut contract = init_tests();
+	as_account(judge());
+	contract.grant_role(carol(), Role::MarketModerator);
+	as_account(carol());
+	contract.delete_market(0);
+	assert_eq!(contract.get_all_markets().len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "only market moderators can delete markets")]
+fn test_delete_market_without_role() {
+	let mut contract = init_tests();
+	contract.delete_market(0);
+}
+
+#[test]
+fn test_single_judge_finalizes() {
+	let mut contract = init_tests();
+	await_judge(&mut contract);
+	vote(&mut contract, judge(), Some(vec![0, 100]));
+	assert_eq!(contract.get_market_status(0), MarketStatus::Finalized(Some(vec![0, 100])));
+}
+
+#[test]
+fn test_judge_threshold() {
+	let mut contract = init_tests();
+	as_account(judge());
+	contract.grant_role(bob(), Role::Judge);
+	contract.set_judge_threshold(2);
+	await_judge(&mut contract);
+
+	vote(&mut contract, judge(), Some(vec![0, 100]));
+	assert_eq!(contract.get_market_status(0), MarketStatus::AwaitingJudge);
+	vote(&mut contract, bob(), None);
+	assert_eq!(contract.get_market_status(0), MarketStatus::AwaitingJudge);
+	assert_eq!(contract.get_judge_votes(0).len(), 2);
+
+	// Judges can change their vote
+	vote(&mut contract, bob(), Some(vec![0, 100]));
+	assert_eq!(contract.get_market_status(0), MarketStatus::Finalized(Some(vec![0, 100])));
+}
+
+#[test]
+fn test_revoked_judge_vote_is_dropped() {
+	let mut contract = init_tests();
+	as_account(judge());
+	contract.grant_role(bob(), Role::Judge);
+	contract.grant_role(alice(), Role::Judge);
+	contract.set_judge_threshold(2);
+	await_judge(&mut contract);
+
+	vote(&mut contract, bob(), Some(vec![0, 100]));
+	as_account(judge());
+	contract.revoke_role(bob(), Role::Judge);
+	vote(&mut contract, alice(), Some(vec![0, 100]));
+	assert_eq!(contract.get_market_status(0), MarketStatus::AwaitingJudge);
+	vote(&mut contract, judge(), Some(vec![0, 100]));
+	assert_eq!(contract.get_market_status(0), MarketStatus::Finalized(Some(vec![0, 100])));
+}
+
+#[test]
+#[should_panic(expected = "judge threshold should be larger than 0")]
+fn test_empty_judge_threshold() {
+	let mut contract = init_tests();
+	as_account(judge());
+	contract.set_judge_threshold(0);
+}
+
+#[test]
+#[should_panic(expected = "judge threshold can't be larger than the number of judges")]
+fn test_judge_threshold_above_judges() {
+	let mut contract = init_tests();
+	as_account(judge());
+	contract.grant_role(bob(), Role::Judge);
+	contract.set_judge_threshold(3);
+}
+
+#[test]
+#[should_panic(expected = "not enough judges would be left to reach the judge threshold")]
+fn test_revoke_judge_below_threshold() {
+	let mut contract = init_tests();
+	as_account(judge());
+	contract.grant_role(bob(), Role::Judge);
+	contract.set_judge_threshold(2);
+	contract.revoke_role(bob(), Role::Judge);
+}
@@ -0,0 +1,168 @@
+use super::*;
+
+fn bytes_cost(bytes: u64) -> u128 {
+	return bytes as u128 * storage::STORAGE_PRICE_PER_BYTE;
+}
+
+fn attach_one_yocto() {
+	let mut context = get_context(carol(), current_block_timestamp());
+	context.attached_deposit = 1;
+	testing_env!(context);
+}
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	return contract;
+}
+
+#[test]
+fn test_storage_deposit() {
+	let contract = init_tests();
+	let balance = contract.storage_balance_of(carol()).unwrap();
+	assert_eq!(balance.total, storage_deposit_amount());
+	assert_eq!(balance.available, storage_deposit_amount() - bytes_cost(storage::BALANCE_ENTRY_BYTES));
+	assert_eq!(contract.storage_balance_of(alice()), None);
+}
+
+#[test]
+fn test_storage_deposit_for_other_account() {
+	let mut contract = init_tests();
+	let mut context = get_context(carol(), current_block_timestamp());
+	context.attached_deposit = storage_deposit_amount();
+	testing_env!(context);
+	contract.storage_deposit(Some(alice()));
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	contract.claim_fdai();
+	let balance = contract.storage_balance_of(alice()).unwrap();
+	assert_eq!(balance.total, storage_deposit_amount());
+}
+
+#[test]
+#[should_panic(expected = "attached deposit is less than the minimum storage balance")]
+fn test_storage_deposit_below_minimum() {
+	let mut contract = init_tests();
+	let mut context = get_context(alice(), current_block_timestamp());
+	context.attached_deposit = contract.storage_balance_bounds().min - 1;
+	testing_env!(context);
+	contract.storage_deposit(None);
+}
+
+#[test]
+fn test_storage_top_up_below_minimum() {
+	let mut contract = init_tests();
+	let mut context = get_context(carol(), current_block_timestamp());
+	context.attached_deposit = 1;
+	testing_env!(context);
+	let balance = contract.storage_deposit(None);
+	assert_eq!(balance.total, storage_deposit_amount() + 1);
+}
+
+#[test]
+#[should_panic(expected = "account has no storage deposit")]
+fn test_claim_fdai_without_storage() {
+	let mut contract = init_tests();
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	contract.claim_fdai();
+}
+
+#[test]
+#[should_panic(expected = "insufficient storage deposit")]
+fn test_order_exceeding_storage_deposit() {
+	let mut contract = init_tests();
+	let mut context = get_context(alice(), current_block_timestamp());
+	context.attached_deposit = contract.storage_balance_bounds().min;
+	testing_env!(context);
+	contract.storage_deposit(None);
+	contract.claim_fdai();
+	contract.place_order(0, 0, 5000, 50);
+}
+
+#[test]
+fn test_storage_released_on_cancel() {
+	let mut contract = init_tests();
+	contract.place_order(0, 0, 5000, 50);
+	let balance = contract.storage_balance_of(carol()).unwrap();
+	assert_eq!(balance.available, storage_deposit_amount() - bytes_cost(storage::BALANCE_ENTRY_BYTES + storage::ORDER_ENTRY_BYTES));
+
+	contract.cancel_order(0, 0, 0);
+	let balance = contract.storage_balance_of(carol()).unwrap();
+	assert_eq!(balance.available, storage_deposit_amount() - bytes_cost(storage::BALANCE_ENTRY_BYTES));
+}
+
+#[test]
+fn test_storage_released_on_claim() {
+	let mut contract = init_tests();
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 1, 5000, 50);
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	let balance = contract.storage_balance_of(carol()).unwrap();
+	assert_eq!(balance.available, storage_deposit_amount() - bytes_cost(storage::BALANCE_ENTRY_BYTES + 2 * storage::ORDER_ENTRY_BYTES + storage::STAKE_ENTRY_BYTES));
+
+	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
+	contract.finalize_market(0, None);
+	contract.claim_earnings(0, carol());
+	let balance = contract.storage_balance_of(carol()).unwrap();
+	assert_eq!(balance.available, storage_deposit_amount() - bytes_cost(storage::BALANCE_ENTRY_BYTES));
+}
+
+#[test]
+fn test_storage_released_on_stake_withdrawal() {
+	let mut contract = init_tests();
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	register_storage(&mut contract, alice(), market_end_timestamp_ns());
+	contract.claim_fdai();
+	contract.resolute_market(0, Some(vec![0, 100]), to_dai(4));
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	contract.dispute_market(0, Some(vec![0, 100]), to_dai(10));
+	testing_env!(get_context(judge(), market_end_timestamp_ns()));
+	contract.finalize_market(0, Some(vec![100, 0]));
+
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	let available = contract.storage_balance_of(alice()).unwrap().available;
+	contract.withdraw_dispute_stake(0, 0, Some(vec![0, 100]));
+	assert_eq!(contract.storage_balance_of(alice()).unwrap().available, available + bytes_cost(storage::STAKE_ENTRY_BYTES));
+
+	// Claiming afterwards only releases what's left
+	contract.claim_earnings(0, alice());
+	assert_eq!(contract.storage_balance_of(alice()).unwrap().available, storage_deposit_amount() - bytes_cost(storage::BALANCE_ENTRY_BYTES));
+}
+
+#[test]
+fn test_storage_withdraw() {
+	let mut contract = init_tests();
+	contract.place_order(0, 0, 5000, 50);
+	let locked = bytes_cost(storage::BALANCE_ENTRY_BYTES + storage::ORDER_ENTRY_BYTES);
+
+	attach_one_yocto();
+	let balance = contract.storage_withdraw(Some(storage_deposit_amount() / 2));
+	assert_eq!(balance.total, storage_deposit_amount() / 2);
+
+	let balance = contract.storage_withdraw(None);
+	assert_eq!(balance.total, locked);
+	assert_eq!(balance.available, 0);
+}
+
+#[test]
+#[should_panic(expected = "amount exceeds available storage balance")]
+fn test_storage_withdraw_locked_balance() {
+	let mut contract = init_tests();
+	attach_one_yocto();
+	contract.storage_withdraw(Some(storage_deposit_amount()));
+}
+
+#[test]
+#[should_panic(expected = "requires attached deposit of exactly 1 yoctoNEAR")]
+fn test_storage_withdraw_without_yocto() {
+	let mut contract = init_tests();
+	contract.storage_withdraw(None);
+}
@@ -0,0 +1,71 @@
+use super::*;
+use market::trades::{TradeHistory, MAX_TRADES};
+
+fn second_trade_timestamp_ns() -> u64 {
+	return time::ms_to_ns(2 * time::HOUR);
+}
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	allow_self_trades(&mut contract);
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), time::ns_to_ms(second_trade_timestamp_ns()) + time::HOUR, 0, 0, "test".to_string());
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 1, 5000, 50);
+
+	testing_env!(get_context(carol(), second_trade_timestamp_ns()));
+	contract.place_order(0, 0, 3000, 30);
+	contract.place_order(0, 1, 7000, 70);
+	return contract;
+}
+
+#[test]
+fn test_trades_recorded_on_fill() {
+	let contract = init_tests();
+	let trades = contract.get_trades(0, 0, 10);
+	assert_eq!(trades.len(), 4);
+	assert_eq!(trades[0], &Trade { time: 0, outcome: 1, price: 50, shares: 100 });
+	assert_eq!(trades[1], &Trade { time: 0, outcome: 0, price: 50, shares: 100 });
+	assert_eq!(trades[2], &Trade { time: 2 * time::HOUR, outcome: 1, price: 70, shares: 100 });
+	assert_eq!(trades[3], &Trade { time: 2 * time::HOUR, outcome: 0, price: 30, shares: 100 });
+
+	assert_eq!(contract.get_trades(0, 3, 10).len(), 1);
+	assert_eq!(contract.get_trades(0, 1, 2)[0], trades[1]);
+}
+
+#[test]
+fn test_candles() {
+	let contract = init_tests();
+	assert_eq!(contract.get_candles(0, 1, time::HOUR), vec![
+		Candle { start_time: 0, open: 50, high: 50, low: 50, close: 50, volume: 100 },
+		Candle { start_time: 2 * time::HOUR, open: 70, high: 70, low: 70, close: 70, volume: 100 },
+	]);
+	assert_eq!(contract.get_candles(0, 0, 3 * time::HOUR), vec![
+		Candle { start_time: 0, open: 50, high: 50, low: 30, close: 30, volume: 200 },
+	]);
+}
+
+#[test]
+#[should_panic(expected = "interval should be larger than 0")]
+fn test_candles_without_interval() {
+	let contract = init_tests();
+	contract.get_candles(0, 0, 0);
+}
+
+#[test]
+fn test_trade_ring_buffer() {
+	let mut history = TradeHistory::default();
+	for i in 0..MAX_TRADES + 5 {
+		history.record(Trade { time: i, outcome: 0, price: 50, shares: 1 });
+	}
+	assert_eq!(history.trades.len() as u64, MAX_TRADES);
+	assert_eq!(history.first_retained(), 5);
+
+	let trades = history.get_trades(0, 2);
+	assert_eq!(trades[0].time, 5);
+	assert_eq!(trades[1].time, 6);
+	assert_eq!(history.get_trades(MAX_TRADES + 4, 10)[0].time, MAX_TRADES + 4);
+	assert_eq!(history.get_candles(0, 1).len() as u64, MAX_TRADES);
+}
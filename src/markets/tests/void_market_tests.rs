@@ -0,0 +1,147 @@
+use super::*;
+
+fn init_tests() -> Markets {
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	contract.place_order(0, 0, 5000, 50);
+	contract.place_order(0, 1, 1000, 20);
+
+	testing_env!(get_context(alice(), current_block_timestamp()));
+	register_storage(&mut contract, alice(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.place_order(0, 1, 3000, 50);
+	return contract;
+}
+
+// Carol reports and alice disputes so both have stakes in the market
+fn dispute(contract: &mut Markets) {
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	contract.dispute_market(0, None, to_dai(10));
+}
+
+fn void(contract: &mut Markets) {
+	testing_env!(get_context(judge(), market_end_timestamp_ns()));
+	contract.void_market(0);
+}
+
+fn claim(contract: &mut Markets, account_id: String) -> u128 {
+	testing_env!(get_context(account_id.to_string(), market_end_timestamp_ns()));
+	let balance_before = contract.get_fdai_balance(account_id.to_string());
+	contract.claim_earnings(0, account_id.to_string());
+	return contract.get_fdai_balance(account_id) - balance_before;
+}
+
+#[test]
+fn test_void_refunds_spend_and_stakes() {
+	let mut contract = init_tests();
+	dispute(&mut contract);
+	void(&mut contract);
+	assert_eq!(contract.get_market_status(0), MarketStatus::Voided);
+	assert_eq!(contract.get_claimable(0, carol()), 6000 + to_dai(5));
+
+	assert_eq!(claim(&mut contract, carol()), 6000 + to_dai(5));
+	assert_eq!(claim(&mut contract, alice()), 3000 + to_dai(10));
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100));
+	assert_eq!(contract.get_fdai_balance(alice()), to_dai(100));
+	assert_eq!(claim(&mut contract, carol()), 0);
+}
+
+#[test]
+fn test_void_refunds_stakes_above_the_bond() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(8));
+	testing_env!(get_context(alice(), market_end_timestamp_ns()));
+	contract.dispute_market(0, None, to_dai(12));
+	void(&mut contract);
+
+	// Only the bonds were kept, what was staked above them was returned right away
+	assert_eq!(contract.get_claimable(0, carol()), 6000 + to_dai(5));
+	assert_eq!(claim(&mut contract, carol()), 6000 + to_dai(5));
+	assert_eq!(claim(&mut contract, alice()), 3000 + to_dai(10));
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100));
+	assert_eq!(contract.get_fdai_balance(alice()), to_dai(100));
+
+	testing_env!(get_context(judge(), market_end_timestamp_ns()));
+	contract.delete_market(0);
+}
+
+#[test]
+fn test_cancel_after_void() {
+	let mut contract = init_tests();
+	void(&mut contract);
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.cancel_order(0, 1, 0);
+	assert_eq!(claim(&mut contract, carol()), 5000);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100));
+}
+
+#[test]
+#[should_panic(expected = "market isn't open for trading")]
+fn test_trading_after_void() {
+	let mut contract = init_tests();
+	void(&mut contract);
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	contract.place_order(0, 0, 1000, 10);
+}
+
+#[test]
+#[should_panic(expected = "market still holds funds, void it first")]
+fn test_delete_market_holding_funds() {
+	let mut contract = init_tests();
+	void(&mut contract);
+	contract.delete_market(0);
+}
+
+#[test]
+fn test_delete_after_refunds() {
+	let mut contract = init_tests();
+	dispute(&mut contract);
+	void(&mut contract);
+	claim(&mut contract, carol());
+	claim(&mut contract, alice());
+
+	testing_env!(get_context(judge(), market_end_timestamp_ns()));
+	contract.delete_market(0);
+	assert_eq!(contract.get_all_markets().len(), 0);
+	assert_eq!(contract.storage_balance_of(carol()).unwrap().available, storage_deposit_amount() - storage::BALANCE_ENTRY_BYTES as u128 * storage::STORAGE_PRICE_PER_BYTE);
+}
+
+#[test]
+fn test_void_returns_creation_bond() {
+	testing_env!(get_context(judge(), current_block_timestamp()));
+	let mut contract = Markets::default();
+	contract.set_creation_bond(to_dai(1));
+	testing_env!(get_context(carol(), current_block_timestamp()));
+	register_storage(&mut contract, carol(), current_block_timestamp());
+	contract.claim_fdai();
+	contract.create_market("Hi!".to_string(), empty_string(), 2, outcome_tags(0), categories(), market_end_timestamp_ms(), 0, 0, "test".to_string());
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(99));
+
+	void(&mut contract);
+	assert_eq!(contract.get_fdai_balance(carol()), to_dai(100));
+	contract.delete_market(0);
+}
+
+#[test]
+#[should_panic(expected = "market is already finalized")]
+fn test_void_finalized_market() {
+	let mut contract = init_tests();
+	testing_env!(get_context(carol(), market_end_timestamp_ns()));
+	contract.resolute_market(0, Some(vec![100, 0]), to_dai(5));
+	testing_env!(get_context(carol(), market_end_timestamp_ns() + 1800000000000));
+	contract.finalize_market(0, None);
+	void(&mut contract);
+}
+
+#[test]
+#[should_panic(expected = "only market moderators can void markets")]
+fn test_void_without_role() {
+	let mut contract = init_tests();
+	contract.void_market(0);
+}
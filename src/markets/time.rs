@@ -0,0 +1,58 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use near_sdk::env;
+use super::market::MarketStatus;
+
+// Market times are stored in ms, env::block_timestamp is in ns - always convert through these helpers
+pub const NS_PER_MS: u64 = 1000000;
+pub const SECOND: u64 = 1000;
+pub const MINUTE: u64 = 60 * SECOND;
+pub const HOUR: u64 = 60 * MINUTE;
+
+pub fn ns_to_ms(
+	ns: u64
+) -> u64 {
+	return ns / NS_PER_MS;
+}
+
+pub fn ms_to_ns(
+	ms: u64
+) -> u64 {
+	return ms * NS_PER_MS;
+}
+
+// Current block time in ms
+pub fn now_ms() -> u64 {
+	return ns_to_ms(env::block_timestamp());
+}
+
+// Window lengths a market goes through after its end_time, all in ms
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct WindowConfig {
+	pub grace_period: u64, // Between end_time and reporting opening
+	pub designated_reporting_period: u64, // Only the designated reporter can resolute, 0 disables it
+	pub dispute_window: u64, // Length of each resolution window after the market is resoluted
+}
+
+impl Default for WindowConfig {
+	fn default() -> Self {
+		Self {
+			grace_period: 0,
+			designated_reporting_period: 0,
+			dispute_window: 30 * MINUTE,
+		}
+	}
+}
+
+// Expected deadlines of a market, all in ms
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MarketTimeline {
+	pub status: MarketStatus,
+	pub now: u64,
+	pub creation_time: u64,
+	pub end_time: u64,
+	pub reporting_start_time: u64,
+	pub designated_reporting_end_time: u64,
+	pub resolution_window_round: u64,
+	pub resolution_window_end_time: Option<u64>, // End of the active dispute window, None until the market is resoluted
+}